@@ -1,5 +1,9 @@
+use crate::models::connection::SSHConfig;
 use crate::modules::database::get_db;
+use crate::modules::ssh::openssh_config::{self, UnmappedEntry};
+use crate::modules::ssh::triggers::get_trigger_manager;
 use chrono::Utc;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 /// 创建连接
@@ -89,6 +93,12 @@ pub async fn delete_connection(id: String) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to delete connection: {}", e))?;
 
+    // 连接的触发规则已被级联删除，刷新内存中的规则缓存
+    get_trigger_manager()
+        .reload()
+        .await
+        .map_err(|e| format!("Failed to reload triggers: {}", e))?;
+
     Ok(())
 }
 
@@ -141,6 +151,42 @@ pub async fn export_connections() -> Result<String, String> {
         .map_err(|e| format!("Failed to serialize connections: {}", e))
 }
 
+/// 导出 SSH 连接为 OpenSSH 配置文本（可按分组过滤，不包含密码和口令）
+#[tauri::command]
+pub async fn export_ssh_config(group_id: Option<String>) -> Result<String, String> {
+    let connections = list_connections(group_id).await?;
+
+    let mut header = format!(
+        "# Generated by DevHub at {}\n",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    let mut entries = Vec::new();
+    for conn in connections
+        .into_iter()
+        .filter(|c| c.connection_type == "ssh")
+    {
+        let config = serde_json::from_str::<SSHConfig>(&conn.config)
+            .map_err(|e| format!("invalid config ({})", e))
+            .and_then(|config| {
+                openssh_config::check_exportable(&config)
+                    .map(|_| config)
+                    .map_err(|e| e.to_string())
+            });
+        match config {
+            Ok(config) => entries.push((conn.name, config)),
+            Err(e) => header.push_str(&format!(
+                "# Skipped {}: {}\n",
+                openssh_config::comment_text(&conn.name),
+                openssh_config::comment_text(&e)
+            )),
+        }
+    }
+
+    let body = openssh_config::export(&entries)
+        .map_err(|e| format!("Failed to export ssh config: {}", e))?;
+    Ok(format!("{}\n{}", header, body))
+}
+
 /// 导入连接（JSON 格式）
 #[tauri::command]
 pub async fn import_connections(json: String) -> Result<usize, String> {
@@ -177,6 +223,99 @@ pub async fn import_connections(json: String) -> Result<usize, String> {
     Ok(count)
 }
 
+/// 从 OpenSSH 配置文件导入 SSH 连接（默认 ~/.ssh/config）
+/// 已存在同名 SSH 连接的主机会被跳过，并与无法映射的条目一起返回
+/// 所有连接在一个事务中写入，任一写入失败时不导入任何连接
+#[tauri::command]
+pub async fn import_ssh_config(
+    path: Option<String>,
+    group_id: Option<String>,
+) -> Result<SshConfigImportResult, String> {
+    let db = get_db();
+
+    // 读取配置文件（含 Include 的文件）是阻塞 IO
+    let report = tokio::task::spawn_blocking(move || openssh_config::import(path.as_deref()))
+        .await
+        .map_err(|e| format!("Failed to parse ssh config: {}", e))?
+        .map_err(|e| format!("Failed to parse ssh config: {}", e))?;
+
+    let mut tx = db
+        .pool()
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to import connection: {}", e))?;
+
+    let existing: Vec<(String,)> =
+        sqlx::query_as("SELECT name FROM connections WHERE type = 'ssh'")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to list connections: {}", e))?;
+    let existing: HashSet<String> = existing.into_iter().map(|(name,)| name).collect();
+
+    let mut result = SshConfigImportResult {
+        imported: Vec::new(),
+        unmapped: report.unmapped,
+    };
+
+    for host in report.hosts {
+        if existing.contains(&host.name) {
+            result.unmapped.push(UnmappedEntry {
+                host: host.name,
+                reason: "Connection with the same name already exists".to_string(),
+            });
+            continue;
+        }
+
+        let config = serde_json::to_string(&host.config)
+            .map_err(|e| format!("Failed to serialize connection: {}", e))?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO connections (id, name, type, group_id, config, created_at, updated_at)
+            VALUES (?, ?, 'ssh', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&host.name)
+        .bind(&group_id)
+        .bind(&config)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to import connection: {}", e))?;
+
+        result.imported.push(ImportedSshConnection {
+            id,
+            name: host.name,
+            ignored_options: host.ignored_options,
+        });
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to import connection: {}", e))?;
+
+    Ok(result)
+}
+
+/// ssh config 导入结果
+#[derive(Debug, serde::Serialize)]
+pub struct SshConfigImportResult {
+    pub imported: Vec<ImportedSshConnection>,
+    pub unmapped: Vec<UnmappedEntry>,
+}
+
+/// 已导入的 SSH 连接
+#[derive(Debug, serde::Serialize)]
+pub struct ImportedSshConnection {
+    pub id: String,
+    pub name: String,
+    pub ignored_options: Vec<String>,
+}
+
 /// 原始连接数据（从数据库查询）
 #[derive(Debug, serde::Serialize)]
 pub struct ConnectionRaw {
@@ -1,9 +1,11 @@
 pub mod connection;
 pub mod ssh;
+pub mod sftp;
 pub mod database;
 
 pub use connection::*;
 pub use ssh::*;
+pub use sftp::*;
 pub use database::*;
 
 // Tauri Commands
@@ -15,11 +17,6 @@ pub fn greet(name: &str) -> Result<String, String> {
 
 // SFTP 相关命令
 // TODO: Task 3.1
-// - sftp_list_dir
-// - sftp_upload
-// - sftp_download
-// - sftp_delete
-// - sftp_rename
 // - sftp_read_file
 // - sftp_write_file
 
@@ -0,0 +1,134 @@
+use crate::modules::ssh::sftp::{get_sftp_manager, SftpEntry};
+use crate::modules::ssh::transfer::{get_transfer_manager, TransferInfo};
+use tauri::command;
+
+/// 在已保存的连接上打开 SFTP（通过连接池共享连接），返回的 ID 可代替 session_id 使用
+#[command]
+pub async fn sftp_open_connection(connection_id: String) -> Result<String, String> {
+    get_sftp_manager()
+        .open_on_connection(&connection_id)
+        .await
+        .map_err(|e| format!("Failed to open SFTP: {}", e))
+}
+
+/// 关闭 SFTP（通过连接池打开的 SFTP 关闭且传输结束后释放连接）
+#[command]
+pub async fn sftp_close(session_id: String) -> Result<(), String> {
+    get_sftp_manager().close_session(&session_id).await;
+    Ok(())
+}
+
+/// 列出远程目录
+#[command]
+pub async fn sftp_list_dir(session_id: String, path: String) -> Result<Vec<SftpEntry>, String> {
+    get_sftp_manager()
+        .list_dir(&session_id, &path)
+        .await
+        .map_err(|e| format!("Failed to list directory: {}", e))
+}
+
+/// 获取远程文件信息
+#[command]
+pub async fn sftp_stat(session_id: String, path: String) -> Result<SftpEntry, String> {
+    get_sftp_manager()
+        .stat(&session_id, &path)
+        .await
+        .map_err(|e| format!("Failed to stat: {}", e))
+}
+
+/// 创建远程目录
+#[command]
+pub async fn sftp_mkdir(session_id: String, path: String) -> Result<(), String> {
+    get_sftp_manager()
+        .mkdir(&session_id, &path)
+        .await
+        .map_err(|e| format!("Failed to create directory: {}", e))
+}
+
+/// 删除远程文件或目录
+#[command]
+pub async fn sftp_delete(
+    session_id: String,
+    path: String,
+    recursive: Option<bool>,
+) -> Result<(), String> {
+    get_sftp_manager()
+        .delete(&session_id, &path, recursive.unwrap_or(false))
+        .await
+        .map_err(|e| format!("Failed to delete: {}", e))
+}
+
+/// 重命名远程文件或目录
+#[command]
+pub async fn sftp_rename(
+    session_id: String,
+    old_path: String,
+    new_path: String,
+) -> Result<(), String> {
+    get_sftp_manager()
+        .rename(&session_id, &old_path, &new_path)
+        .await
+        .map_err(|e| format!("Failed to rename: {}", e))
+}
+
+/// 修改远程文件权限
+#[command]
+pub async fn sftp_chmod(session_id: String, path: String, mode: u32) -> Result<(), String> {
+    get_sftp_manager()
+        .chmod(&session_id, &path, mode)
+        .await
+        .map_err(|e| format!("Failed to chmod: {}", e))
+}
+
+/// 上传文件（返回传输 ID，进度通过 sftp-progress-{id} 事件推送）
+#[command]
+pub async fn sftp_upload(
+    session_id: String,
+    local_path: String,
+    remote_path: String,
+    resume: Option<bool>,
+) -> Result<String, String> {
+    get_transfer_manager()
+        .upload(
+            &session_id,
+            &local_path,
+            &remote_path,
+            resume.unwrap_or(false),
+        )
+        .await
+        .map_err(|e| format!("Failed to start upload: {}", e))
+}
+
+/// 下载文件（返回传输 ID，进度通过 sftp-progress-{id} 事件推送）
+#[command]
+pub async fn sftp_download(
+    session_id: String,
+    remote_path: String,
+    local_path: String,
+    resume: Option<bool>,
+) -> Result<String, String> {
+    get_transfer_manager()
+        .download(
+            &session_id,
+            &remote_path,
+            &local_path,
+            resume.unwrap_or(false),
+        )
+        .await
+        .map_err(|e| format!("Failed to start download: {}", e))
+}
+
+/// 取消传输
+#[command]
+pub async fn sftp_cancel_transfer(transfer_id: String) -> Result<(), String> {
+    get_transfer_manager()
+        .cancel(&transfer_id)
+        .await
+        .map_err(|e| format!("Failed to cancel transfer: {}", e))
+}
+
+/// 列出传输（可按会话过滤）
+#[command]
+pub async fn sftp_list_transfers(session_id: Option<String>) -> Result<Vec<TransferInfo>, String> {
+    Ok(get_transfer_manager().list(session_id.as_deref()).await)
+}
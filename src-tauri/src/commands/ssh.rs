@@ -1,4 +1,28 @@
-use crate::modules::ssh::client::get_ssh_manager;
+use crate::models::connection::JumpHostConfig;
+use crate::modules::ssh::agent::{self, AgentIdentity};
+use crate::modules::ssh::auth_prompt::get_auth_prompt_manager;
+use crate::modules::ssh::cert::{self, CertificateInfo};
+use crate::modules::ssh::client::{
+    get_ssh_manager, SessionInfo, SessionOptions, SessionStats, WriteResult,
+};
+use crate::modules::ssh::exec::{get_exec_manager, ExecResult};
+use crate::modules::ssh::forward::{get_forward_manager, PortForwardInfo};
+use crate::modules::ssh::keys::{self, CopyIdResult, KeyAlgorithm, KeyInfo};
+use crate::modules::ssh::known_hosts::{self, KnownHost, KnownHostsOptions};
+use crate::modules::ssh::monitor::{self, get_monitor_manager, HostMetrics, MetricsSample};
+use crate::modules::ssh::pool::{get_connection_pool, PooledConnectionInfo};
+use crate::modules::ssh::processes::{
+    self, ProcessList, ProcessQuery, ProcessSignal, SignalResult, SudoOptions,
+};
+use crate::modules::ssh::recorder::{
+    get_recording_manager, recording_path, recordings_dir, RecordingInfo,
+};
+use crate::modules::ssh::replay::{get_replay_manager, ReplayInfo};
+use crate::modules::ssh::scrollback::ScrollbackChunk;
+use crate::modules::ssh::triggers::{self, Trigger, TriggerAction};
+use base64::Engine;
+use std::path::Path;
+use std::time::Duration;
 use tauri::{command, AppHandle};
 
 /// 初始化 SSH 管理器（在应用启动时调用）
@@ -9,8 +33,11 @@ pub fn init_ssh_manager(app_handle: AppHandle) {
 }
 
 /// SSH 连接
+/// - options 为保活与自动重连选项，不传时使用默认值
+/// - connection_id 为已保存连接的 ID，用于会话列表展示
 #[command]
 pub async fn ssh_connect(
+    connection_id: Option<String>,
     host: String,
     port: u16,
     username: String,
@@ -18,11 +45,15 @@ pub async fn ssh_connect(
     password: Option<String>,
     key_path: Option<String>,
     passphrase: Option<String>,
+    cert_path: Option<String>,
+    jump_host: Option<JumpHostConfig>,
+    options: Option<SessionOptions>,
 ) -> Result<String, String> {
     let manager = get_ssh_manager();
 
     let session_id = manager
         .create_session(
+            connection_id,
             host,
             port,
             username,
@@ -30,6 +61,9 @@ pub async fn ssh_connect(
             password.as_deref(),
             key_path.as_deref(),
             passphrase.as_deref(),
+            cert_path.as_deref(),
+            jump_host.as_ref(),
+            options.unwrap_or_default(),
         )
         .await
         .map_err(|e| format!("Failed to connect: {}", e))?;
@@ -37,6 +71,42 @@ pub async fn ssh_connect(
     Ok(session_id)
 }
 
+/// 使用已保存的连接打开终端（同一连接的多个终端共用一个已认证的连接）
+#[command]
+pub async fn ssh_connect_saved(
+    connection_id: String,
+    options: Option<SessionOptions>,
+) -> Result<String, String> {
+    get_ssh_manager()
+        .create_pooled_session(&connection_id, options.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to connect: {}", e))
+}
+
+/// 列出连接池中的共享连接
+#[command]
+pub async fn ssh_list_pooled_connections() -> Result<Vec<PooledConnectionInfo>, String> {
+    Ok(get_connection_pool().list().await)
+}
+
+/// 回答 keyboard-interactive 认证提示（ssh-auth-prompt 事件）
+#[command]
+pub async fn ssh_auth_respond(request_id: String, responses: Vec<String>) -> Result<(), String> {
+    get_auth_prompt_manager()
+        .respond(&request_id, responses)
+        .await
+        .map_err(|e| format!("Failed to respond to prompt: {}", e))
+}
+
+/// 取消 keyboard-interactive 认证提示
+#[command]
+pub async fn ssh_auth_cancel(request_id: String) -> Result<(), String> {
+    get_auth_prompt_manager()
+        .cancel(&request_id)
+        .await
+        .map_err(|e| format!("Failed to cancel prompt: {}", e))
+}
+
 /// SSH 断开连接
 #[command]
 pub async fn ssh_disconnect(session_id: String) -> Result<(), String> {
@@ -51,23 +121,108 @@ pub async fn ssh_disconnect(session_id: String) -> Result<(), String> {
 }
 
 /// SSH 写入数据
+/// - 会话处于广播集合中时，同步写入集合内的所有会话并返回每个会话的写入结果
 #[command]
-pub async fn ssh_write(session_id: String, data: String) -> Result<(), String> {
+pub async fn ssh_write(session_id: String, data: String) -> Result<Vec<WriteResult>, String> {
+    write_bytes(&session_id, data.as_bytes()).await
+}
+
+/// SSH 写入原始字节（Base64 编码），用于粘贴二进制数据或非 UTF-8 内容
+#[command]
+pub async fn ssh_write_binary(
+    session_id: String,
+    data: String,
+) -> Result<Vec<WriteResult>, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("Invalid base64 data: {}", e))?;
+
+    write_bytes(&session_id, &bytes).await
+}
+
+async fn write_bytes(session_id: &str, data: &[u8]) -> Result<Vec<WriteResult>, String> {
     let manager = get_ssh_manager();
 
-    manager
-        .write_to_session(&session_id, data.as_bytes())
+    let results = manager.write_with_broadcast(session_id, data).await;
+
+    // 目标会话本身写入失败时保持原有的报错行为
+    if let Some(WriteResult {
+        error: Some(error), ..
+    }) = results.iter().find(|r| r.session_id == session_id)
+    {
+        return Err(format!("Failed to write: {}", error));
+    }
+
+    Ok(results)
+}
+
+/// 确认已处理的终端输出字节数（输出流控）
+/// - 前端在 xterm 写入完成后按 ssh-data 事件中 data 解码后的字节数确认
+/// - 从未确认过的会话不会因流控暂停
+#[command]
+pub async fn ssh_ack_output(session_id: String, bytes: usize) -> Result<(), String> {
+    get_ssh_manager()
+        .ack_output(&session_id, bytes)
+        .await
+        .map_err(|e| format!("Failed to acknowledge output: {}", e))
+}
+
+/// 读取会话的回滚缓冲区（offset 为上次返回的 next_offset，不传时从缓冲区开头读取）
+#[command]
+pub async fn ssh_read_scrollback(
+    session_id: String,
+    offset: Option<u64>,
+    max_bytes: Option<usize>,
+) -> Result<ScrollbackChunk, String> {
+    get_ssh_manager()
+        .read_scrollback(&session_id, offset, max_bytes)
+        .await
+        .map(ScrollbackChunk::from)
+        .map_err(|e| format!("Failed to read scrollback: {}", e))
+}
+
+/// 重新附加到正在运行的会话（前端重新加载或新窗口）
+/// - 先监听 ssh-data-{id} 事件再调用，返回的回滚内容之后的输出按事件中的 offset 续接
+#[command]
+pub async fn ssh_attach_session(
+    session_id: String,
+    offset: Option<u64>,
+    max_bytes: Option<usize>,
+) -> Result<ScrollbackChunk, String> {
+    get_ssh_manager()
+        .attach_session(&session_id, offset, max_bytes)
         .await
-        .map_err(|e| format!("Failed to write: {}", e))?;
+        .map(ScrollbackChunk::from)
+        .map_err(|e| format!("Failed to attach session: {}", e))
+}
 
+/// 设置广播会话集合（传入空列表关闭广播）
+#[command]
+pub async fn ssh_set_broadcast(session_ids: Vec<String>) -> Result<(), String> {
+    get_ssh_manager().set_broadcast(session_ids).await;
     Ok(())
 }
 
-/// 列出所有活跃的 SSH 会话
+/// 获取广播会话集合
 #[command]
-pub async fn ssh_list_sessions() -> Result<Vec<String>, String> {
+pub async fn ssh_get_broadcast() -> Result<Vec<String>, String> {
+    Ok(get_ssh_manager().get_broadcast().await)
+}
+
+/// 列出所有活跃的 SSH 会话（主机、用户、状态、终端大小、流量等）
+#[command]
+pub async fn ssh_list_sessions() -> Result<Vec<SessionInfo>, String> {
     let manager = get_ssh_manager();
-    Ok(manager.list_sessions().await)
+    Ok(manager.list_session_infos().await)
+}
+
+/// 获取单个会话的流量统计
+#[command]
+pub async fn ssh_session_stats(session_id: String) -> Result<SessionStats, String> {
+    get_ssh_manager()
+        .session_stats(&session_id)
+        .await
+        .map_err(|e| format!("Failed to get session stats: {}", e))
 }
 
 /// 调整 SSH 终端窗口大小
@@ -88,3 +243,474 @@ pub async fn ssh_resize_window(
 
     Ok(())
 }
+
+/// 列出本地 ssh-agent 中的身份
+#[command]
+pub async fn ssh_agent_list_identities() -> Result<Vec<AgentIdentity>, String> {
+    agent::list_identities()
+        .await
+        .map_err(|e| format!("Failed to list agent identities: {}", e))
+}
+
+/// 解析 OpenSSH 证书（主体、有效期、剩余时间等）
+#[command]
+pub fn ssh_cert_inspect(path: String) -> Result<CertificateInfo, String> {
+    cert::inspect(&path).map_err(|e| format!("Failed to inspect certificate: {}", e))
+}
+
+/// 生成密钥对并保存到应用的密钥存储目录
+#[command]
+pub async fn ssh_key_generate(
+    app_handle: AppHandle,
+    name: String,
+    algorithm: KeyAlgorithm,
+    bits: Option<u32>,
+    comment: Option<String>,
+    passphrase: Option<String>,
+) -> Result<KeyInfo, String> {
+    let dir = keys::keys_dir(&app_handle).map_err(|e| format!("Failed to generate key: {}", e))?;
+
+    // RSA 密钥生成较慢，放到阻塞线程中执行
+    tokio::task::spawn_blocking(move || {
+        keys::generate(
+            &dir,
+            &name,
+            algorithm,
+            bits,
+            comment.as_deref(),
+            passphrase.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to generate key: {}", e))?
+    .map_err(|e| format!("Failed to generate key: {}", e))
+}
+
+/// 列出密钥存储目录中的密钥
+#[command]
+pub async fn ssh_key_list(app_handle: AppHandle) -> Result<Vec<KeyInfo>, String> {
+    let dir = keys::keys_dir(&app_handle).map_err(|e| format!("Failed to list keys: {}", e))?;
+
+    tokio::task::spawn_blocking(move || keys::list(&dir))
+        .await
+        .map_err(|e| format!("Failed to list keys: {}", e))?
+        .map_err(|e| format!("Failed to list keys: {}", e))
+}
+
+/// 查看任意路径密钥的类型、指纹和注释
+#[command]
+pub async fn ssh_key_inspect(path: String) -> Result<KeyInfo, String> {
+    tokio::task::spawn_blocking(move || keys::read_key_info(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Failed to inspect key: {}", e))?
+        .map_err(|e| format!("Failed to inspect key: {}", e))
+}
+
+/// 修改私钥口令（new_passphrase 为空表示移除口令）
+#[command]
+pub async fn ssh_key_change_passphrase(
+    path: String,
+    old_passphrase: Option<String>,
+    new_passphrase: Option<String>,
+) -> Result<KeyInfo, String> {
+    // 口令的 bcrypt 密钥派生较慢，放到阻塞线程中执行
+    tokio::task::spawn_blocking(move || {
+        keys::change_passphrase(
+            Path::new(&path),
+            old_passphrase.as_deref(),
+            new_passphrase.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to change passphrase: {}", e))?
+    .map_err(|e| format!("Failed to change passphrase: {}", e))
+}
+
+/// 将公钥部署到已连接会话的 ~/.ssh/authorized_keys（ssh-copy-id）
+#[command]
+pub async fn ssh_key_copy_id(
+    session_id: String,
+    public_key: String,
+) -> Result<CopyIdResult, String> {
+    keys::copy_id(&session_id, &public_key)
+        .await
+        .map_err(|e| format!("Failed to deploy public key: {}", e))
+}
+
+/// 列出所有已信任的主机密钥
+#[command]
+pub async fn ssh_known_hosts_list() -> Result<Vec<KnownHost>, String> {
+    known_hosts::list()
+        .await
+        .map_err(|e| format!("Failed to list known hosts: {}", e))
+}
+
+/// 接受（或替换）主机密钥
+#[command]
+pub async fn ssh_known_hosts_accept(
+    host: String,
+    port: u16,
+    key_type: String,
+    public_key: String,
+) -> Result<KnownHost, String> {
+    known_hosts::accept(&host, port, &key_type, &public_key)
+        .await
+        .map_err(|e| format!("Failed to accept host key: {}", e))
+}
+
+/// 撤销主机的已信任密钥
+#[command]
+pub async fn ssh_known_hosts_revoke(host: String, port: u16) -> Result<bool, String> {
+    known_hosts::revoke(&host, port)
+        .await
+        .map_err(|e| format!("Failed to revoke host key: {}", e))
+}
+
+/// 获取 ~/.ssh/known_hosts 同步选项
+#[command]
+pub async fn ssh_known_hosts_get_options() -> Result<KnownHostsOptions, String> {
+    known_hosts::get_options()
+        .await
+        .map_err(|e| format!("Failed to get known hosts options: {}", e))
+}
+
+/// 设置 ~/.ssh/known_hosts 同步选项
+#[command]
+pub async fn ssh_known_hosts_set_options(options: KnownHostsOptions) -> Result<(), String> {
+    known_hosts::set_options(options)
+        .await
+        .map_err(|e| format!("Failed to set known hosts options: {}", e))
+}
+
+/// 列出终端输出触发规则（指定 connection_id 时返回对该连接生效的规则，包括全局规则）
+#[command]
+pub async fn ssh_triggers_list(connection_id: Option<String>) -> Result<Vec<Trigger>, String> {
+    triggers::list(connection_id.as_deref())
+        .await
+        .map_err(|e| format!("Failed to list triggers: {}", e))
+}
+
+/// 创建或更新触发规则（id 为空时创建，connection_id 为空时对所有会话生效）
+#[command]
+pub async fn ssh_triggers_save(
+    id: Option<String>,
+    name: String,
+    connection_id: Option<String>,
+    pattern: String,
+    actions: Vec<TriggerAction>,
+    enabled: bool,
+) -> Result<Trigger, String> {
+    triggers::save(
+        id.as_deref(),
+        &name,
+        connection_id.as_deref(),
+        &pattern,
+        &actions,
+        enabled,
+    )
+    .await
+    .map_err(|e| format!("Failed to save trigger: {}", e))
+}
+
+/// 删除触发规则
+#[command]
+pub async fn ssh_triggers_delete(id: String) -> Result<bool, String> {
+    triggers::delete(&id)
+        .await
+        .map_err(|e| format!("Failed to delete trigger: {}", e))
+}
+
+/// 开始监控会话所在主机的资源使用情况（interval_secs 默认 5 秒）
+/// - 指标通过 ssh-metrics-{id} 事件推送
+#[command]
+pub async fn ssh_monitor_start(
+    session_id: String,
+    interval_secs: Option<u64>,
+) -> Result<(), String> {
+    let interval = interval_secs
+        .map(Duration::from_secs)
+        .unwrap_or(monitor::DEFAULT_INTERVAL);
+
+    get_monitor_manager()
+        .start(&session_id, interval)
+        .await
+        .map_err(|e| format!("Failed to start monitor: {}", e))
+}
+
+/// 停止监控
+#[command]
+pub async fn ssh_monitor_stop(session_id: String) -> Result<bool, String> {
+    Ok(get_monitor_manager().stop(&session_id).await)
+}
+
+/// 获取最近一次采集的主机指标
+#[command]
+pub async fn ssh_monitor_latest(session_id: String) -> Result<Option<HostMetrics>, String> {
+    Ok(get_monitor_manager().latest(&session_id).await)
+}
+
+/// 查询主机最近 minutes 分钟（默认 60）的监控历史
+#[command]
+pub async fn ssh_monitor_history(
+    host: String,
+    port: u16,
+    minutes: Option<u32>,
+) -> Result<Vec<MetricsSample>, String> {
+    monitor::history(&host, port, minutes.unwrap_or(60))
+        .await
+        .map_err(|e| format!("Failed to query monitor history: {}", e))
+}
+
+/// 列出会话所在主机的进程（可过滤、排序，默认按 CPU 使用率降序）
+#[command]
+pub async fn ssh_process_list(
+    session_id: String,
+    query: Option<ProcessQuery>,
+) -> Result<ProcessList, String> {
+    processes::list(&session_id, &query.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to list processes: {}", e))
+}
+
+/// 向进程发送 TERM / KILL / HUP 信号
+/// - sudo 不为空时通过 sudo 发送（提供 password 时经标准输入传给 sudo -S）
+#[command]
+pub async fn ssh_process_signal(
+    session_id: String,
+    pids: Vec<u32>,
+    signal: ProcessSignal,
+    sudo: Option<SudoOptions>,
+) -> Result<Vec<SignalResult>, String> {
+    processes::signal(&session_id, &pids, signal, sudo.as_ref())
+        .await
+        .map_err(|e| format!("Failed to send signal: {}", e))
+}
+
+/// 打开本地端口转发（ssh -L）
+#[command]
+pub async fn ssh_forward_local_open(
+    session_id: String,
+    bind_address: Option<String>,
+    bind_port: u16,
+    target_host: String,
+    target_port: u16,
+) -> Result<PortForwardInfo, String> {
+    let bind_address = bind_address.unwrap_or_else(|| "127.0.0.1".to_string());
+
+    get_forward_manager()
+        .open_local(
+            &session_id,
+            &bind_address,
+            bind_port,
+            &target_host,
+            target_port,
+        )
+        .await
+        .map_err(|e| format!("Failed to open local port forward: {}", e))
+}
+
+/// 打开动态端口转发（ssh -D，本地 SOCKS5 代理）
+#[command]
+pub async fn ssh_forward_dynamic_open(
+    session_id: String,
+    bind_address: Option<String>,
+    bind_port: u16,
+) -> Result<PortForwardInfo, String> {
+    let bind_address = bind_address.unwrap_or_else(|| "127.0.0.1".to_string());
+
+    get_forward_manager()
+        .open_dynamic(&session_id, &bind_address, bind_port)
+        .await
+        .map_err(|e| format!("Failed to open SOCKS5 proxy: {}", e))
+}
+
+/// 打开远程端口转发（ssh -R）
+#[command]
+pub async fn ssh_forward_remote_open(
+    session_id: String,
+    remote_address: Option<String>,
+    remote_port: u16,
+    local_host: Option<String>,
+    local_port: u16,
+) -> Result<PortForwardInfo, String> {
+    let remote_address = remote_address.unwrap_or_else(|| "localhost".to_string());
+    let local_host = local_host.unwrap_or_else(|| "127.0.0.1".to_string());
+
+    get_forward_manager()
+        .open_remote(
+            &session_id,
+            &remote_address,
+            remote_port,
+            &local_host,
+            local_port,
+        )
+        .await
+        .map_err(|e| format!("Failed to open remote port forward: {}", e))
+}
+
+/// 列出端口转发（可按会话过滤）
+#[command]
+pub async fn ssh_forward_list(session_id: Option<String>) -> Result<Vec<PortForwardInfo>, String> {
+    Ok(get_forward_manager().list(session_id.as_deref()).await)
+}
+
+/// 关闭端口转发
+#[command]
+pub async fn ssh_forward_close(forward_id: String) -> Result<(), String> {
+    get_forward_manager()
+        .close(&forward_id)
+        .await
+        .map_err(|e| format!("Failed to close port forward: {}", e))
+}
+
+/// 执行单条远程命令（exec channel，不分配 PTY）
+/// - 在已有会话（session_id）或已保存的连接（connection_id）上执行
+/// - exec_id 可由前端预先生成，以便在执行前订阅 ssh-exec-stdout-{id} / ssh-exec-stderr-{id} 事件
+#[command]
+pub async fn ssh_exec(
+    command: String,
+    session_id: Option<String>,
+    connection_id: Option<String>,
+    timeout_secs: Option<u64>,
+    exec_id: Option<String>,
+) -> Result<ExecResult, String> {
+    let manager = get_exec_manager();
+    let timeout = timeout_secs.map(Duration::from_secs);
+
+    let result = match (session_id, connection_id) {
+        (Some(session_id), _) => {
+            manager
+                .exec_on_session(exec_id, &session_id, &command, timeout)
+                .await
+        }
+        (None, Some(connection_id)) => {
+            manager
+                .exec_on_connection(exec_id, &connection_id, &command, timeout)
+                .await
+        }
+        (None, None) => return Err("Either session_id or connection_id is required".to_string()),
+    };
+
+    result.map_err(|e| format!("Failed to execute command: {}", e))
+}
+
+/// 取消正在执行的远程命令
+#[command]
+pub async fn ssh_exec_cancel(exec_id: String) -> Result<(), String> {
+    get_exec_manager()
+        .cancel(&exec_id)
+        .await
+        .map_err(|e| format!("Failed to cancel command: {}", e))
+}
+
+/// 开始录制会话（asciicast v2 格式）
+/// - include_input 为 true 时同时记录用户输入
+#[command]
+pub async fn ssh_recording_start(
+    app_handle: AppHandle,
+    session_id: String,
+    title: Option<String>,
+    include_input: Option<bool>,
+) -> Result<RecordingInfo, String> {
+    let term_size = get_ssh_manager()
+        .term_size(&session_id)
+        .await
+        .map_err(|e| format!("Failed to start recording: {}", e))?;
+    let dir =
+        recordings_dir(&app_handle).map_err(|e| format!("Failed to start recording: {}", e))?;
+
+    get_recording_manager()
+        .start(
+            &session_id,
+            &dir,
+            term_size.cols,
+            term_size.rows,
+            title,
+            include_input.unwrap_or(false),
+        )
+        .await
+        .map_err(|e| format!("Failed to start recording: {}", e))
+}
+
+/// 停止录制会话
+#[command]
+pub async fn ssh_recording_stop(session_id: String) -> Result<RecordingInfo, String> {
+    get_recording_manager()
+        .stop(&session_id)
+        .await
+        .map_err(|e| format!("Failed to stop recording: {}", e))
+}
+
+/// 列出所有录像
+#[command]
+pub async fn ssh_recording_list(app_handle: AppHandle) -> Result<Vec<RecordingInfo>, String> {
+    let dir =
+        recordings_dir(&app_handle).map_err(|e| format!("Failed to list recordings: {}", e))?;
+
+    get_recording_manager()
+        .list(&dir)
+        .map_err(|e| format!("Failed to list recordings: {}", e))
+}
+
+/// 加载录像并创建回放
+/// - 回放输出通过 ssh-data-{replay_id} 事件推送，可直接复用终端组件
+/// - 窗口大小变化通过 ssh-replay-resize-{replay_id}，播放状态通过 ssh-replay-state-{replay_id} 推送
+#[command]
+pub async fn ssh_replay_open(
+    app_handle: AppHandle,
+    recording_id: String,
+) -> Result<ReplayInfo, String> {
+    let path = recordings_dir(&app_handle)
+        .and_then(|dir| recording_path(&dir, &recording_id))
+        .map_err(|e| format!("Failed to open replay: {}", e))?;
+
+    get_replay_manager()
+        .open(&recording_id, &path, app_handle)
+        .await
+        .map_err(|e| format!("Failed to open replay: {}", e))
+}
+
+/// 开始（继续）回放
+#[command]
+pub async fn ssh_replay_play(replay_id: String) -> Result<(), String> {
+    get_replay_manager()
+        .play(&replay_id)
+        .await
+        .map_err(|e| format!("Failed to play replay: {}", e))
+}
+
+/// 暂停回放
+#[command]
+pub async fn ssh_replay_pause(replay_id: String) -> Result<(), String> {
+    get_replay_manager()
+        .pause(&replay_id)
+        .await
+        .map_err(|e| format!("Failed to pause replay: {}", e))
+}
+
+/// 跳转到指定时间（秒）
+#[command]
+pub async fn ssh_replay_seek(replay_id: String, position: f64) -> Result<(), String> {
+    get_replay_manager()
+        .seek(&replay_id, position)
+        .await
+        .map_err(|e| format!("Failed to seek replay: {}", e))
+}
+
+/// 设置回放速度倍数
+#[command]
+pub async fn ssh_replay_set_speed(replay_id: String, speed: f64) -> Result<(), String> {
+    get_replay_manager()
+        .set_speed(&replay_id, speed)
+        .await
+        .map_err(|e| format!("Failed to set replay speed: {}", e))
+}
+
+/// 关闭回放
+#[command]
+pub async fn ssh_replay_close(replay_id: String) -> Result<(), String> {
+    get_replay_manager()
+        .close(&replay_id)
+        .await
+        .map_err(|e| format!("Failed to close replay: {}", e))
+}
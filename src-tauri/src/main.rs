@@ -39,12 +39,74 @@ fn main() {
             commands::delete_connection,
             commands::list_connections,
             commands::export_connections,
+            commands::export_ssh_config,
             commands::import_connections,
+            commands::import_ssh_config,
             commands::ssh_connect,
+            commands::ssh_connect_saved,
+            commands::ssh_auth_respond,
+            commands::ssh_auth_cancel,
             commands::ssh_disconnect,
             commands::ssh_write,
+            commands::ssh_write_binary,
+            commands::ssh_ack_output,
+            commands::ssh_read_scrollback,
+            commands::ssh_attach_session,
+            commands::ssh_set_broadcast,
+            commands::ssh_get_broadcast,
             commands::ssh_list_sessions,
+            commands::ssh_list_pooled_connections,
+            commands::ssh_session_stats,
             commands::ssh_resize_window,
+            commands::ssh_agent_list_identities,
+            commands::ssh_cert_inspect,
+            commands::ssh_key_generate,
+            commands::ssh_key_list,
+            commands::ssh_key_inspect,
+            commands::ssh_key_change_passphrase,
+            commands::ssh_key_copy_id,
+            commands::ssh_known_hosts_list,
+            commands::ssh_known_hosts_accept,
+            commands::ssh_known_hosts_revoke,
+            commands::ssh_known_hosts_get_options,
+            commands::ssh_known_hosts_set_options,
+            commands::ssh_triggers_list,
+            commands::ssh_triggers_save,
+            commands::ssh_triggers_delete,
+            commands::ssh_monitor_start,
+            commands::ssh_monitor_stop,
+            commands::ssh_monitor_latest,
+            commands::ssh_monitor_history,
+            commands::ssh_process_list,
+            commands::ssh_process_signal,
+            commands::ssh_forward_local_open,
+            commands::ssh_forward_remote_open,
+            commands::ssh_forward_dynamic_open,
+            commands::ssh_forward_list,
+            commands::ssh_forward_close,
+            commands::ssh_exec,
+            commands::ssh_exec_cancel,
+            commands::ssh_recording_start,
+            commands::ssh_recording_stop,
+            commands::ssh_recording_list,
+            commands::ssh_replay_open,
+            commands::ssh_replay_play,
+            commands::ssh_replay_pause,
+            commands::ssh_replay_seek,
+            commands::ssh_replay_set_speed,
+            commands::ssh_replay_close,
+            commands::sftp_open_connection,
+            commands::sftp_close,
+            commands::sftp_list_dir,
+            commands::sftp_stat,
+            commands::sftp_mkdir,
+            commands::sftp_delete,
+            commands::sftp_rename,
+            commands::sftp_chmod,
+            commands::sftp_upload,
+            commands::sftp_download,
+            commands::sftp_cancel_transfer,
+            commands::sftp_list_transfers,
             commands::mysql_connect,
             commands::postgresql_connect,
             commands::mysql_query,
@@ -34,7 +34,7 @@ pub enum ConnectionType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Config {
-    Ssh(SSHConfig),
+    Ssh(Box<SSHConfig>),
     Database(DatabaseConfig),
 }
 
@@ -53,6 +53,9 @@ pub struct SSHConfig {
     pub private_key_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub passphrase: Option<String>,
+    /// OpenSSH 证书路径（与 private_key_path 配合使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jump_host: Option<JumpHostConfig>,
 }
@@ -65,6 +68,23 @@ pub struct SSHConfig {
 pub enum AuthMethod {
     Password,
     Key,
+    /// 使用本地 ssh-agent（SSH_AUTH_SOCK）中的身份
+    Agent,
+    /// keyboard-interactive（PAM、OTP 等），提示由前端回答
+    #[serde(rename = "keyboard-interactive")]
+    KeyboardInteractive,
+}
+
+impl AuthMethod {
+    /// 与 SSHSessionManager::create_session 约定的认证方式名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthMethod::Password => "password",
+            AuthMethod::Key => "key",
+            AuthMethod::Agent => "agent",
+            AuthMethod::KeyboardInteractive => "keyboard-interactive",
+        }
+    }
 }
 
 /**
@@ -84,6 +104,8 @@ pub struct DatabaseConfig {
 
 /**
  * 跳板机配置
+ *
+ * 多级跳板时，`jump_host` 指向连接本跳板机之前需要经过的上一跳
  */
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JumpHostConfig {
@@ -95,6 +117,12 @@ pub struct JumpHostConfig {
     pub password: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub private_key_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jump_host: Option<Box<JumpHostConfig>>,
 }
 
 /**
@@ -154,6 +182,7 @@ mod tests {
             password: Some("pass".to_string()),
             private_key_path: None,
             passphrase: None,
+            certificate_path: None,
             jump_host: None,
         };
 
@@ -169,7 +198,7 @@ mod tests {
             name: "Test SSH".to_string(),
             connection_type: ConnectionType::Ssh,
             group_id: None,
-            config: Config::Ssh(SSHConfig {
+            config: Config::Ssh(Box::new(SSHConfig {
                 host: "localhost".to_string(),
                 port: 22,
                 username: "user".to_string(),
@@ -177,8 +206,9 @@ mod tests {
                 password: Some("pass".to_string()),
                 private_key_path: None,
                 passphrase: None,
+                certificate_path: None,
                 jump_host: None,
-            }),
+            })),
             created_at: "2025-01-01T00:00:00Z".to_string(),
             updated_at: "2025-01-01T00:00:00Z".to_string(),
         };
@@ -187,4 +217,55 @@ mod tests {
         println!("Connection: {}", json);
         assert!(json.contains("\"type\":\"ssh\""));
     }
+
+    #[test]
+    fn test_deserialize_nested_jump_host() {
+        let json = r#"{
+            "host": "10.0.0.5",
+            "port": 22,
+            "username": "app",
+            "auth_method": "key",
+            "private_key_path": "~/.ssh/id_ed25519",
+            "jump_host": {
+                "host": "bastion-inner",
+                "port": 22,
+                "username": "ops",
+                "auth_method": "key",
+                "jump_host": {
+                    "host": "bastion.example.com",
+                    "port": 2222,
+                    "username": "ops",
+                    "auth_method": "password",
+                    "password": "secret"
+                }
+            }
+        }"#;
+
+        let config: SSHConfig = serde_json::from_str(json).unwrap();
+        let inner = config.jump_host.unwrap();
+        assert_eq!(inner.host, "bastion-inner");
+        let outer = inner.jump_host.unwrap();
+        assert_eq!(outer.port, 2222);
+        assert_eq!(outer.auth_method.as_str(), "password");
+        assert!(outer.jump_host.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_agent_auth_method() {
+        let json = r#"{"host": "db-1", "port": 22, "username": "ops", "auth_method": "agent"}"#;
+
+        let config: SSHConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.auth_method.as_str(), "agent");
+        assert!(config.password.is_none());
+    }
+
+    #[test]
+    fn test_keyboard_interactive_auth_method_name() {
+        let method: AuthMethod = serde_json::from_str(r#""keyboard-interactive""#).unwrap();
+        assert_eq!(method.as_str(), "keyboard-interactive");
+        assert_eq!(
+            serde_json::to_string(&method).unwrap(),
+            r#""keyboard-interactive""#
+        );
+    }
 }
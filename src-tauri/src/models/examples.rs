@@ -11,7 +11,7 @@ fn example_create_ssh_connection() -> Connection {
         name: "Production Server".to_string(),
         connection_type: ConnectionType::Ssh,
         group_id: Some("group-1".to_string()),
-        config: Config::Ssh(SSHConfig {
+        config: Config::Ssh(Box::new(SSHConfig {
             host: "192.168.1.100".to_string(),
             port: 22,
             username: "admin".to_string(),
@@ -19,8 +19,9 @@ fn example_create_ssh_connection() -> Connection {
             password: Some("password123".to_string()),
             private_key_path: None,
             passphrase: None,
+            certificate_path: None,
             jump_host: None,
-        }),
+        })),
         created_at: "2025-02-05T00:00:00Z".to_string(),
         updated_at: "2025-02-05T00:00:00Z".to_string(),
     }
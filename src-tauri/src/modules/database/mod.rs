@@ -78,6 +78,83 @@ impl Database {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create connections table: {}", e))?;
 
+        // 创建已信任主机密钥表
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS known_hosts (
+                host TEXT NOT NULL,
+                port INTEGER NOT NULL,
+                key_type TEXT NOT NULL,
+                public_key TEXT NOT NULL,
+                fingerprint TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (host, port)
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create known_hosts table: {}", e))?;
+
+        // 创建主机密钥同步选项表（单行）
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS known_hosts_options (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                read_openssh INTEGER NOT NULL,
+                write_openssh INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create known_hosts_options table: {}", e))?;
+
+        // 创建终端输出触发规则表
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS triggers (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                connection_id TEXT,
+                pattern TEXT NOT NULL,
+                actions TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create triggers table: {}", e))?;
+
+        // 创建主机监控历史表（只保留最近一段时间的数据）
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS host_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                host TEXT NOT NULL,
+                port INTEGER NOT NULL,
+                timestamp TEXT NOT NULL,
+                cpu_usage REAL,
+                memory_used INTEGER,
+                memory_total INTEGER,
+                load1 REAL,
+                disk_used INTEGER NOT NULL,
+                disk_total INTEGER NOT NULL,
+                rx_rate REAL,
+                tx_rate REAL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create host_metrics table: {}", e))?;
+
         // 创建索引
         sqlx::query(
             r#"
@@ -99,9 +176,35 @@ impl Database {
         .await
         .ok();
 
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_host_metrics_host
+            ON host_metrics(host, port, timestamp);
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .ok();
+
         Ok(())
     }
 
+    /// 创建内存数据库（仅用于测试）
+    #[cfg(test)]
+    pub async fn in_memory() -> Result<Self> {
+        // 内存库每个连接相互独立，限制为单连接
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+
+        let db = Database { pool };
+        db.init_tables().await?;
+
+        Ok(db)
+    }
+
     /// 获取连接池引用
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
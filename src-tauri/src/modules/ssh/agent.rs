@@ -0,0 +1,110 @@
+use super::client::SSHClientHandler;
+#[cfg(unix)]
+use super::forward::{tunnel, ForwardStats};
+use super::known_hosts;
+use anyhow::{anyhow, Result};
+use russh::client::{Handle, Msg};
+use russh::keys::key::PublicKey;
+use russh::Channel;
+use serde::Serialize;
+
+/// 通过 SSH_AUTH_SOCK 连接的 agent 客户端
+#[cfg(unix)]
+type UnixAgentClient = russh_keys::agent::client::AgentClient<tokio::net::UnixStream>;
+
+/// ssh-agent 中的身份（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentIdentity {
+    pub key_type: String,
+    pub fingerprint: String,
+}
+
+impl From<&PublicKey> for AgentIdentity {
+    fn from(key: &PublicKey) -> Self {
+        Self {
+            key_type: key.name().to_string(),
+            fingerprint: known_hosts::fingerprint(key),
+        }
+    }
+}
+
+/// 列出 ssh-agent 中的身份
+#[cfg(unix)]
+pub async fn list_identities() -> Result<Vec<AgentIdentity>> {
+    let mut agent = connect_agent().await?;
+    let identities = request_identities(&mut agent).await?;
+    Ok(identities.iter().map(AgentIdentity::from).collect())
+}
+
+/// 使用 ssh-agent 中的身份依次尝试公钥认证
+#[cfg(unix)]
+pub async fn authenticate(handle: &mut Handle<SSHClientHandler>, username: &str) -> Result<bool> {
+    let mut agent = connect_agent().await?;
+    let identities = request_identities(&mut agent).await?;
+    if identities.is_empty() {
+        return Err(anyhow!("SSH agent has no identities"));
+    }
+
+    for key in identities {
+        let fingerprint = known_hosts::fingerprint(&key);
+        let (returned, result) = handle.authenticate_future(username, key, agent).await;
+        agent = returned;
+
+        match result {
+            Ok(true) => return Ok(true),
+            Ok(false) => {}
+            Err(e) => log::warn!("SSH agent failed to sign with {}: {}", fingerprint, e),
+        }
+    }
+
+    Ok(false)
+}
+
+/// 将服务器打开的 agent 转发 channel 接到本地 SSH_AUTH_SOCK
+#[cfg(unix)]
+pub async fn forward_channel(channel: Channel<Msg>) {
+    let Some(socket_path) = std::env::var_os("SSH_AUTH_SOCK") else {
+        log::warn!("Agent forwarding requested but SSH_AUTH_SOCK is not set");
+        let _ = channel.close().await;
+        return;
+    };
+
+    match tokio::net::UnixStream::connect(&socket_path).await {
+        Ok(socket) => tunnel(socket, channel.into_stream(), &ForwardStats::default()).await,
+        Err(e) => {
+            log::warn!("Failed to connect to SSH agent: {}", e);
+            let _ = channel.close().await;
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn connect_agent() -> Result<UnixAgentClient> {
+    UnixAgentClient::connect_env()
+        .await
+        .map_err(|e| anyhow!("Failed to connect to SSH agent (SSH_AUTH_SOCK): {}", e))
+}
+
+#[cfg(unix)]
+async fn request_identities(agent: &mut UnixAgentClient) -> Result<Vec<PublicKey>> {
+    agent
+        .request_identities()
+        .await
+        .map_err(|e| anyhow!("Failed to list SSH agent identities: {}", e))
+}
+
+#[cfg(not(unix))]
+pub async fn list_identities() -> Result<Vec<AgentIdentity>> {
+    Err(anyhow!("SSH agent is only supported on Unix platforms"))
+}
+
+#[cfg(not(unix))]
+pub async fn authenticate(_handle: &mut Handle<SSHClientHandler>, _username: &str) -> Result<bool> {
+    Err(anyhow!("SSH agent is only supported on Unix platforms"))
+}
+
+#[cfg(not(unix))]
+pub async fn forward_channel(channel: Channel<Msg>) {
+    log::warn!("Agent forwarding is only supported on Unix platforms");
+    let _ = channel.close().await;
+}
@@ -0,0 +1,232 @@
+use super::client::SSHClientHandler;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use russh::client::{Handle, KeyboardInteractiveAuthResponse, Prompt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+/// 等待用户回答提示的超时时间
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 单次认证最多处理的提示轮数（防止服务器无限发送提示）
+const MAX_ROUNDS: usize = 16;
+
+/// 服务器的单个提示
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthPromptItem {
+    pub prompt: String,
+    /// 为 false 时输入内容不应回显（密码、OTP 等）
+    pub echo: bool,
+}
+
+/// 发送给前端的认证提示（ssh-auth-prompt 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthPromptRequest {
+    pub request_id: String,
+    pub session_id: String,
+    pub username: String,
+    pub name: String,
+    pub instructions: String,
+    pub prompts: Vec<AuthPromptItem>,
+    pub timeout_secs: u64,
+}
+
+/// 等待前端回答的认证提示
+pub struct AuthPromptManager {
+    pending: Mutex<HashMap<String, oneshot::Sender<Vec<String>>>>,
+}
+
+impl AuthPromptManager {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 向前端发送提示并等待回答
+    async fn ask(
+        &self,
+        app_handle: &tauri::AppHandle,
+        mut request: AuthPromptRequest,
+    ) -> Result<Vec<String>> {
+        let request_id = Uuid::new_v4().to_string();
+        request.request_id = request_id.clone();
+        request.timeout_secs = PROMPT_TIMEOUT.as_secs();
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(request_id.clone(), tx);
+
+        let _ = app_handle.emit_all("ssh-auth-prompt", &request);
+
+        let result = tokio::time::timeout(PROMPT_TIMEOUT, rx).await;
+        self.pending.lock().await.remove(&request_id);
+
+        match result {
+            Ok(Ok(responses)) => Ok(responses),
+            Ok(Err(_)) => Err(anyhow!("Authentication prompt was cancelled")),
+            Err(_) => Err(anyhow!(
+                "Timed out waiting for authentication prompt response"
+            )),
+        }
+    }
+
+    /// 回答提示（responses 与提示一一对应）
+    pub async fn respond(&self, request_id: &str, responses: Vec<String>) -> Result<()> {
+        let tx = self
+            .pending
+            .lock()
+            .await
+            .remove(request_id)
+            .ok_or_else(|| anyhow!("Authentication prompt not found: {}", request_id))?;
+
+        tx.send(responses)
+            .map_err(|_| anyhow!("Authentication prompt is no longer waiting"))
+    }
+
+    /// 取消提示，认证随之失败
+    pub async fn cancel(&self, request_id: &str) -> Result<()> {
+        self.pending
+            .lock()
+            .await
+            .remove(request_id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Authentication prompt not found: {}", request_id))
+    }
+}
+
+/// 全局认证提示管理器
+static AUTH_PROMPT_MANAGER: Lazy<AuthPromptManager> = Lazy::new(AuthPromptManager::new);
+
+pub fn get_auth_prompt_manager() -> &'static AuthPromptManager {
+    &AUTH_PROMPT_MANAGER
+}
+
+/// keyboard-interactive 认证
+/// - 每一轮服务器提示通过 ssh-auth-prompt 事件发送给前端，等待 ssh_auth_respond 回答
+/// - 提供了密码时，自动回答第一次出现的单个密码提示（PAM 密码 + OTP 的常见组合）
+pub async fn authenticate(
+    handle: &mut Handle<SSHClientHandler>,
+    username: &str,
+    password: Option<&str>,
+    session_id: &str,
+    app_handle: Option<&tauri::AppHandle>,
+) -> Result<bool> {
+    let mut password = password;
+    let mut response = handle
+        .authenticate_keyboard_interactive_start(username, None)
+        .await
+        .map_err(|e| anyhow!("Keyboard-interactive authentication failed: {}", e))?;
+
+    for _ in 0..MAX_ROUNDS {
+        let (name, instructions, prompts) = match response {
+            KeyboardInteractiveAuthResponse::Success => return Ok(true),
+            KeyboardInteractiveAuthResponse::Failure => return Ok(false),
+            KeyboardInteractiveAuthResponse::InfoRequest {
+                name,
+                instructions,
+                prompts,
+            } => (name, instructions, prompts),
+        };
+
+        let answers = if prompts.is_empty() {
+            // 只有说明文字的轮次，按协议回复空列表
+            Vec::new()
+        } else if let Some(answer) = password.and_then(|p| auto_answer(&prompts, p)) {
+            password = None;
+            answer
+        } else {
+            let app_handle = app_handle.ok_or_else(|| {
+                anyhow!("Keyboard-interactive authentication requires user interaction")
+            })?;
+            let request = AuthPromptRequest {
+                request_id: String::new(),
+                session_id: session_id.to_string(),
+                username: username.to_string(),
+                name,
+                instructions,
+                prompts: prompts
+                    .iter()
+                    .map(|p| AuthPromptItem {
+                        prompt: p.prompt.clone(),
+                        echo: p.echo,
+                    })
+                    .collect(),
+                timeout_secs: 0,
+            };
+            get_auth_prompt_manager().ask(app_handle, request).await?
+        };
+
+        if answers.len() != prompts.len() {
+            return Err(anyhow!(
+                "Expected {} responses, got {}",
+                prompts.len(),
+                answers.len()
+            ));
+        }
+
+        response = handle
+            .authenticate_keyboard_interactive_respond(answers)
+            .await
+            .map_err(|e| anyhow!("Keyboard-interactive authentication failed: {}", e))?;
+    }
+
+    Err(anyhow!("Too many keyboard-interactive prompts"))
+}
+
+/// 单个不回显的密码提示时使用已提供的密码回答
+fn auto_answer(prompts: &[Prompt], password: &str) -> Option<Vec<String>> {
+    match prompts {
+        [prompt] if !prompt.echo && prompt.prompt.to_lowercase().contains("password") => {
+            Some(vec![password.to_string()])
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(text: &str, echo: bool) -> Prompt {
+        Prompt {
+            prompt: text.to_string(),
+            echo,
+        }
+    }
+
+    #[test]
+    fn test_auto_answer_password_prompt() {
+        let prompts = vec![prompt("Password: ", false)];
+        assert_eq!(
+            auto_answer(&prompts, "secret"),
+            Some(vec!["secret".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_auto_answer_skips_other_prompts() {
+        assert_eq!(
+            auto_answer(&[prompt("Verification code: ", false)], "secret"),
+            None
+        );
+        assert_eq!(auto_answer(&[prompt("Password: ", true)], "secret"), None);
+        assert_eq!(
+            auto_answer(
+                &[prompt("Password: ", false), prompt("OTP: ", false)],
+                "secret"
+            ),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_unknown_request() {
+        let manager = AuthPromptManager::new();
+        assert!(manager.respond("missing", vec![]).await.is_err());
+        assert!(manager.cancel("missing").await.is_err());
+    }
+}
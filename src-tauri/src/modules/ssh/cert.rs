@@ -0,0 +1,152 @@
+use anyhow::{anyhow, Result};
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+use ssh_key::{Certificate, HashAlg};
+use std::collections::BTreeMap;
+
+/// 证书有效期状态
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateStatus {
+    Valid,
+    NotYetValid,
+    Expired,
+}
+
+/// OpenSSH 证书信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateInfo {
+    pub path: String,
+    pub key_type: String,
+    pub cert_type: String,
+    pub key_id: String,
+    pub serial: u64,
+    pub principals: Vec<String>,
+    /// 生效时间（RFC 3339）
+    pub valid_after: String,
+    /// 失效时间（RFC 3339），永久有效时为 None
+    pub valid_before: Option<String>,
+    pub status: CertificateStatus,
+    /// 距失效的秒数（已过期时为负数），永久有效时为 None
+    pub expires_in_secs: Option<i64>,
+    /// 签发 CA 的公钥指纹
+    pub ca_fingerprint: String,
+    pub critical_options: BTreeMap<String, String>,
+    pub extensions: Vec<String>,
+}
+
+/// 读取 OpenSSH 证书文件（*-cert.pub）
+pub fn load(path: &str) -> Result<Certificate> {
+    russh_keys::load_openssh_certificate(path)
+        .map_err(|e| anyhow!("Failed to load certificate {}: {}", path, e))
+}
+
+/// 解析证书信息
+pub fn inspect(path: &str) -> Result<CertificateInfo> {
+    let cert = load(path)?;
+    let now = Utc::now().timestamp();
+    let (status, expires_in_secs) = validity(cert.valid_after(), cert.valid_before(), now);
+
+    Ok(CertificateInfo {
+        path: path.to_string(),
+        key_type: cert.algorithm().to_string(),
+        cert_type: if cert.cert_type().is_user() {
+            "user".to_string()
+        } else {
+            "host".to_string()
+        },
+        key_id: cert.key_id().to_string(),
+        serial: cert.serial(),
+        principals: cert.valid_principals().to_vec(),
+        valid_after: format_timestamp(cert.valid_after()),
+        valid_before: (cert.valid_before() != u64::MAX)
+            .then(|| format_timestamp(cert.valid_before())),
+        status,
+        expires_in_secs,
+        ca_fingerprint: cert
+            .signature_key()
+            .fingerprint(HashAlg::Sha256)
+            .to_string(),
+        critical_options: cert
+            .critical_options()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        extensions: cert.extensions().keys().cloned().collect(),
+    })
+}
+
+/// 检查证书当前是否可用于认证
+pub fn ensure_valid(cert: &Certificate) -> Result<()> {
+    let now = Utc::now().timestamp();
+    match validity(cert.valid_after(), cert.valid_before(), now).0 {
+        CertificateStatus::Valid => Ok(()),
+        CertificateStatus::NotYetValid => Err(anyhow!(
+            "Certificate is not valid until {}",
+            format_timestamp(cert.valid_after())
+        )),
+        CertificateStatus::Expired => Err(anyhow!(
+            "Certificate expired at {}",
+            format_timestamp(cert.valid_before())
+        )),
+    }
+}
+
+/// 根据有效期窗口计算状态和剩余秒数（valid_before 为 u64::MAX 表示永久有效）
+fn validity(valid_after: u64, valid_before: u64, now: i64) -> (CertificateStatus, Option<i64>) {
+    let now = now.max(0) as u64;
+    let expires_in = (valid_before != u64::MAX).then(|| valid_before as i64 - now as i64);
+
+    let status = if now < valid_after {
+        CertificateStatus::NotYetValid
+    } else if now >= valid_before {
+        CertificateStatus::Expired
+    } else {
+        CertificateStatus::Valid
+    };
+
+    (status, expires_in)
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validity_window() {
+        assert_eq!(
+            validity(100, 200, 150),
+            (CertificateStatus::Valid, Some(50))
+        );
+        assert_eq!(
+            validity(100, 200, 50),
+            (CertificateStatus::NotYetValid, Some(150))
+        );
+        assert_eq!(
+            validity(100, 200, 260),
+            (CertificateStatus::Expired, Some(-60))
+        );
+    }
+
+    #[test]
+    fn test_validity_forever() {
+        assert_eq!(
+            validity(0, u64::MAX, 1_700_000_000),
+            (CertificateStatus::Valid, None)
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00+00:00");
+        assert_eq!(format_timestamp(u64::MAX), u64::MAX.to_string());
+    }
+}
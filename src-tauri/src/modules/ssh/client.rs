@@ -1,50 +1,534 @@
+use super::agent;
+use super::auth_prompt;
+use super::cert;
+use super::forward::get_forward_manager;
+use super::jump::JumpChain;
+use super::known_hosts::{self, HostKeyVerdict};
+use super::monitor::get_monitor_manager;
+use super::output::{self, FlowControl, OutputBatcher, OutputOptions};
+use super::pool::{get_connection_pool, ConnectionLease};
+use super::recorder::get_recording_manager;
+use super::scrollback::{Scrollback, ScrollbackRead};
+use super::sftp::get_sftp_manager;
+use super::triggers::{self, get_trigger_manager, TriggerMatcher};
+use crate::models::connection::{JumpHostConfig, SSHConfig};
+use crate::modules::database::get_db;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use base64::Engine;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::future::join_all;
 use once_cell::sync::Lazy;
 use russh::client::{self, Config, Handle, Msg};
 use russh::keys::key::PublicKey;
 use russh::{Channel, ChannelId, ChannelMsg, CryptoVec, Disconnect};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::Manager;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify, RwLock};
 use uuid::Uuid;
 
+/// SSH 客户端错误
+#[derive(Debug, thiserror::Error)]
+pub enum SSHClientError {
+    #[error(transparent)]
+    Russh(#[from] russh::Error),
+    #[error(
+        "Host key verification failed for {host}:{port}: the {key_type} key changed (old: {old_fingerprint}, new: {new_fingerprint})"
+    )]
+    HostKeyChanged {
+        host: String,
+        port: u16,
+        key_type: String,
+        old_fingerprint: String,
+        new_fingerprint: String,
+    },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 /// SSH 客户端 Handler 实现
-struct SSHClientHandler;
+pub struct SSHClientHandler {
+    session_id: String,
+    host: String,
+    port: u16,
+    app_handle: Option<tauri::AppHandle>,
+    /// 是否接受服务器打开的 agent 转发 channel
+    /// - 连接池中的连接可能在建立后被请求 agent 转发的会话复用，因此可以在连接建立后开启
+    agent_forwarding: Arc<AtomicBool>,
+    /// 连接池中的共享连接（session_id 为已保存连接的 ID）
+    pooled: bool,
+}
+
+impl SSHClientHandler {
+    pub fn new(
+        session_id: &str,
+        host: &str,
+        port: u16,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            host: host.to_string(),
+            port,
+            app_handle,
+            agent_forwarding: Arc::new(AtomicBool::new(false)),
+            pooled: false,
+        }
+    }
+
+    /// 允许 agent 转发（仅在请求了 agent 转发的连接上开启）
+    pub fn with_agent_forwarding(mut self, enabled: bool) -> Self {
+        self.agent_forwarding = Arc::new(AtomicBool::new(enabled));
+        self
+    }
+
+    /// agent 转发开关（与 Handler 共享，连接建立后仍可开启）
+    pub fn agent_forwarding(&self) -> Arc<AtomicBool> {
+        self.agent_forwarding.clone()
+    }
+
+    /// 标记为连接池中的共享连接，远程端口转发按连接上的所有会话匹配
+    pub fn pooled(mut self) -> Self {
+        self.pooled = true;
+        self
+    }
+
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        if let Some(app_handle) = &self.app_handle {
+            let _ = app_handle.emit_all(event, payload);
+        }
+    }
+}
 
 #[async_trait]
 impl client::Handler for SSHClientHandler {
-    type Error = russh::Error;
+    type Error = SSHClientError;
 
     async fn check_server_key(
         &mut self,
-        _server_public_key: &PublicKey,
+        server_public_key: &PublicKey,
     ) -> std::result::Result<bool, Self::Error> {
-        // 接受所有服务器密钥（生产环境应该验证）
-        Ok(true)
+        match known_hosts::verify_host_key(&self.host, self.port, server_public_key).await? {
+            HostKeyVerdict::Trusted => Ok(true),
+            HostKeyVerdict::FirstSeen(entry) => {
+                self.emit(
+                    "ssh-host-key-new",
+                    json!({
+                        "session_id": self.session_id,
+                        "host": entry.host,
+                        "port": entry.port,
+                        "key_type": entry.key_type,
+                        "fingerprint": entry.fingerprint,
+                    }),
+                );
+                Ok(true)
+            }
+            HostKeyVerdict::Changed {
+                old_fingerprint,
+                new_fingerprint,
+                key_type,
+                public_key,
+            } => {
+                self.emit(
+                    "ssh-host-key-changed",
+                    json!({
+                        "session_id": self.session_id,
+                        "host": self.host,
+                        "port": self.port,
+                        "key_type": key_type,
+                        "public_key": public_key,
+                        "old_fingerprint": old_fingerprint,
+                        "new_fingerprint": new_fingerprint,
+                    }),
+                );
+                Err(SSHClientError::HostKeyChanged {
+                    host: self.host.clone(),
+                    port: self.port,
+                    key_type,
+                    old_fingerprint,
+                    new_fingerprint,
+                })
+            }
+        }
+    }
+
+    /// 服务器为远程端口转发（ssh -R）打开的 channel
+    async fn server_channel_open_forwarded_tcpip(
+        &mut self,
+        channel: Channel<Msg>,
+        connected_address: &str,
+        connected_port: u32,
+        originator_address: &str,
+        originator_port: u32,
+        _session: &mut client::Session,
+    ) -> std::result::Result<(), Self::Error> {
+        if !self.pooled {
+            get_forward_manager()
+                .accept_remote(
+                    &[self.session_id.clone()],
+                    channel,
+                    connected_address,
+                    connected_port as u16,
+                    originator_address,
+                    originator_port as u16,
+                    self.app_handle.clone(),
+                )
+                .await;
+            return Ok(());
+        }
+
+        // 共享连接按连接上的所有会话匹配转发规则
+        // 查询会话表需要加锁，放到独立任务中执行，避免阻塞连接的事件循环
+        let connection_id = self.session_id.clone();
+        let connected_address = connected_address.to_string();
+        let originator_address = originator_address.to_string();
+        let app_handle = self.app_handle.clone();
+        tokio::spawn(async move {
+            let session_ids = get_ssh_manager()
+                .sessions_on_connection(&connection_id)
+                .await;
+            get_forward_manager()
+                .accept_remote(
+                    &session_ids,
+                    channel,
+                    &connected_address,
+                    connected_port as u16,
+                    &originator_address,
+                    originator_port as u16,
+                    app_handle,
+                )
+                .await;
+        });
+        Ok(())
+    }
+
+    /// 服务器为 agent 转发打开的 channel
+    async fn server_channel_open_agent_forward(
+        &mut self,
+        channel: Channel<Msg>,
+        _session: &mut client::Session,
+    ) -> std::result::Result<(), Self::Error> {
+        if !self.agent_forwarding.load(Ordering::Relaxed) {
+            // 未请求 agent 转发的连接不允许服务器访问本地 agent
+            log::warn!(
+                "Rejected agent forwarding channel from {}:{}",
+                self.host,
+                self.port
+            );
+            tokio::spawn(async move {
+                let _ = channel.close().await;
+            });
+            return Ok(());
+        }
+
+        tokio::spawn(agent::forward_channel(channel));
+        Ok(())
+    }
+}
+
+/// 会话保活与自动重连选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionOptions {
+    /// keepalive 间隔（秒），0 表示不发送 keepalive
+    pub keepalive_interval: u64,
+    /// 连续多少次 keepalive 无响应后判定连接已断开
+    pub keepalive_max: usize,
+    /// 连接意外断开时是否自动重连
+    pub auto_reconnect: bool,
+    /// 最大重连次数
+    pub max_reconnect_attempts: u32,
+    /// 首次重连前的等待时间（毫秒），之后每次翻倍
+    pub reconnect_delay_ms: u64,
+    /// 重连等待时间上限（毫秒）
+    pub max_reconnect_delay_ms: u64,
+    /// 是否将本地 ssh-agent 转发到远程主机
+    pub agent_forwarding: bool,
+    /// 终端输出的批量发送与流控
+    pub output: OutputOptions,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            keepalive_interval: 15,
+            keepalive_max: 3,
+            auto_reconnect: true,
+            max_reconnect_attempts: 5,
+            reconnect_delay_ms: 1000,
+            max_reconnect_delay_ms: 30_000,
+            agent_forwarding: false,
+            output: OutputOptions::default(),
+        }
     }
 }
 
+impl SessionOptions {
+    /// 生成客户端配置
+    pub fn client_config(&self) -> Config {
+        Config {
+            keepalive_interval: (self.keepalive_interval > 0)
+                .then(|| Duration::from_secs(self.keepalive_interval)),
+            keepalive_max: self.keepalive_max,
+            ..Default::default()
+        }
+    }
+
+    /// 第 attempt 次（从 1 开始）重连前的等待时间
+    pub fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .reconnect_delay_ms
+            .saturating_mul(factor)
+            .min(self.max_reconnect_delay_ms);
+        Duration::from_millis(delay)
+    }
+}
+
+/// 终端大小
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TermSize {
+    pub cols: u32,
+    pub rows: u32,
+    /// 像素宽度
+    pub width: u32,
+    /// 像素高度
+    pub height: u32,
+}
+
+impl Default for TermSize {
+    fn default() -> Self {
+        Self {
+            cols: 80,
+            rows: 24,
+            width: 640,
+            height: 480,
+        }
+    }
+}
+
+/// 会话状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStatus {
+    Connected,
+    /// 连接意外断开，正在自动重连
+    Reconnecting,
+}
+
+/// 会话的流量与活动统计（读取任务和写入路径共享）
+#[derive(Debug, Default)]
+struct SessionActivity {
+    /// 从服务器收到的字节数
+    bytes_in: AtomicU64,
+    /// 写入到服务器的字节数
+    bytes_out: AtomicU64,
+    /// 最近一次收发数据的时间（Unix 毫秒）
+    last_activity: AtomicI64,
+    reconnects: AtomicU32,
+    reconnecting: AtomicBool,
+}
+
+impl SessionActivity {
+    fn new() -> Self {
+        let activity = Self::default();
+        activity.touch();
+        activity
+    }
+
+    fn touch(&self) {
+        self.last_activity
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
+    fn record_in(&self, bytes: usize) {
+        self.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn record_out(&self, bytes: usize) {
+        self.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn last_activity(&self) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(self.last_activity.load(Ordering::Relaxed))
+            .single()
+            .unwrap_or_else(Utc::now)
+    }
+
+    fn status(&self) -> SessionStatus {
+        if self.reconnecting.load(Ordering::Relaxed) {
+            SessionStatus::Reconnecting
+        } else {
+            SessionStatus::Connected
+        }
+    }
+}
+
+/// 会话列表项
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    /// 打开会话时使用的已保存连接
+    pub connection_id: Option<String>,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub connected_at: String,
+    pub status: SessionStatus,
+    pub term_size: TermSize,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub last_activity: String,
+    /// 已输出的字节偏移量（重新附加时用于续读回滚缓冲区）
+    pub output_offset: u64,
+}
+
+/// 单个会话的流量统计
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStats {
+    pub session_id: String,
+    pub status: SessionStatus,
+    pub connected_at: String,
+    /// 已连接时长（秒）
+    pub uptime_secs: i64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub last_activity: String,
+    /// 距最近一次收发数据的秒数（用于发现卡住的会话）
+    pub idle_secs: i64,
+    /// 自动重连成功的次数
+    pub reconnects: u32,
+    /// 已发送给前端但尚未确认的输出字节数
+    pub unacked_output: usize,
+}
+
+/// 会话的连接参数（重连时重新认证使用）
+#[derive(Clone)]
+struct ConnectParams {
+    host: String,
+    port: u16,
+    username: String,
+    auth_method: String,
+    password: Option<String>,
+    key_path: Option<String>,
+    passphrase: Option<String>,
+    cert_path: Option<String>,
+    jump_host: Option<JumpHostConfig>,
+}
+
+impl From<&SSHConfig> for ConnectParams {
+    fn from(config: &SSHConfig) -> Self {
+        Self {
+            host: config.host.clone(),
+            port: config.port,
+            username: config.username.clone(),
+            auth_method: config.auth_method.as_str().to_string(),
+            password: config.password.clone(),
+            key_path: config.private_key_path.clone(),
+            passphrase: config.passphrase.clone(),
+            cert_path: config.certificate_path.clone(),
+            jump_host: config.jump_host.clone(),
+        }
+    }
+}
+
+/// 共享的连接 Handle
+/// - 打开 channel、发送数据等只需要 &Handle 的请求持有读锁，可以并发执行，
+///   慢请求（如 direct-tcpip 连接不可达的目标）不会阻塞终端输入
+/// - 写锁只用于 tcpip_forward（russh 0.45 中需要 &mut Handle）和重连时替换 Handle
+pub type SharedHandle = Arc<RwLock<Handle<SSHClientHandler>>>;
+
+/// 重连后会话使用的连接
+enum Transport {
+    /// 会话独占的新连接
+    Owned(Handle<SSHClientHandler>, Option<JumpChain>),
+    /// 连接池中共享连接的代数
+    Pooled(u64),
+}
+
 /// SSH 会话句柄
 /// - Handle + ChannelId: 用于写入数据和断开连接
+/// - SharedHandle: 共享 Handle，端口转发等任务在同一连接上打开新的 channel
 /// - Arc<Mutex<Channel>>: 共享 Channel，读取任务用 wait()，resize 用 window_change()
+/// - 自动重连时替换 Handle 和 Channel 的内容，会话 ID 保持不变
 pub struct SSHSessionHandle {
     pub id: String,
+    /// 打开会话时使用的已保存连接
+    pub connection_id: Option<String>,
     pub host: String,
     pub port: u16,
     pub username: String,
-    handle: Handle<SSHClientHandler>,
+    pub connected_at: DateTime<Utc>,
+    handle: SharedHandle,
+    jump_chain: Option<JumpChain>,
     channel_id: ChannelId,
     channel: Arc<Mutex<Channel<Msg>>>,
+    /// 当前终端大小
+    term_size: Mutex<TermSize>,
+    /// 输出流控（前端确认已处理的字节数）
+    flow: Arc<FlowControl>,
+    activity: Arc<SessionActivity>,
+    /// 最近输出的回滚缓冲区（前端重新加载后用于恢复终端内容）
+    scrollback: Arc<Scrollback>,
+    /// 通知读取任务关闭 shell channel
+    shutdown: Arc<Notify>,
+    /// 使用连接池中的共享连接时持有的使用权
+    lease: Option<ConnectionLease>,
+    /// shell channel 所在共享连接的代数
+    transport_generation: u64,
+    params: ConnectParams,
+    options: SessionOptions,
 }
 
 impl SSHSessionHandle {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        id: String,
+        connection_id: Option<String>,
+        handle: SharedHandle,
+        jump_chain: Option<JumpChain>,
+        lease: Option<ConnectionLease>,
+        channel: Channel<Msg>,
+        term_size: TermSize,
+        params: ConnectParams,
+        options: SessionOptions,
+    ) -> Self {
+        Self {
+            id,
+            connection_id,
+            host: params.host.clone(),
+            port: params.port,
+            username: params.username.clone(),
+            connected_at: Utc::now(),
+            handle,
+            jump_chain,
+            channel_id: channel.id(),
+            // 用 Arc<Mutex> 共享 Channel：读取任务用 wait()，resize 用 window_change()
+            channel: Arc::new(Mutex::new(channel)),
+            term_size: Mutex::new(term_size),
+            flow: Arc::new(FlowControl::new(&options.output)),
+            activity: Arc::new(SessionActivity::new()),
+            scrollback: Arc::new(Scrollback::new(options.output.scrollback_bytes)),
+            shutdown: Arc::new(Notify::new()),
+            transport_generation: lease.as_ref().map_or(0, ConnectionLease::generation),
+            lease,
+            params,
+            options,
+        }
+    }
+
     /// 写入数据到 SSH channel
     pub async fn write(&self, data: &[u8]) -> Result<()> {
         self.handle
+            .read()
+            .await
             .data(self.channel_id, CryptoVec::from_slice(data))
             .await
             .map_err(|_| anyhow!("Failed to write to SSH channel"))?;
@@ -57,23 +541,458 @@ impl SSHSessionHandle {
         ch.window_change(cols, rows, width, height)
             .await
             .map_err(|e| anyhow!("Failed to resize window: {}", e))?;
+        *self.term_size.lock().await = TermSize {
+            cols,
+            rows,
+            width,
+            height,
+        };
         Ok(())
     }
 
+    /// 获取当前终端大小
+    pub async fn term_size(&self) -> TermSize {
+        *self.term_size.lock().await
+    }
+
+    /// 会话列表项
+    pub async fn info(&self) -> SessionInfo {
+        SessionInfo {
+            session_id: self.id.clone(),
+            connection_id: self.connection_id.clone(),
+            host: self.host.clone(),
+            port: self.port,
+            username: self.username.clone(),
+            connected_at: self.connected_at.to_rfc3339(),
+            status: self.activity.status(),
+            term_size: self.term_size().await,
+            bytes_in: self.activity.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.activity.bytes_out.load(Ordering::Relaxed),
+            last_activity: self.activity.last_activity().to_rfc3339(),
+            output_offset: self.scrollback.end_offset(),
+        }
+    }
+
+    /// 流量统计
+    pub fn stats(&self) -> SessionStats {
+        let now = Utc::now();
+        let last_activity = self.activity.last_activity();
+        SessionStats {
+            session_id: self.id.clone(),
+            status: self.activity.status(),
+            connected_at: self.connected_at.to_rfc3339(),
+            uptime_secs: (now - self.connected_at).num_seconds(),
+            bytes_in: self.activity.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.activity.bytes_out.load(Ordering::Relaxed),
+            last_activity: last_activity.to_rfc3339(),
+            idle_secs: (now - last_activity).num_seconds().max(0),
+            reconnects: self.activity.reconnects.load(Ordering::Relaxed),
+            unacked_output: self.flow.unacked(),
+        }
+    }
+
     /// 关闭会话
+    /// - 共享连接上的会话只关闭自己的 shell channel，连接由连接池在最后一个使用者释放时断开
     pub async fn close(&self) -> Result<()> {
+        self.shutdown.notify_one();
+        if self.lease.is_some() {
+            return Ok(());
+        }
+
         self.handle
+            .read()
+            .await
             .disconnect(Disconnect::ByApplication, "", "English")
             .await
             .map_err(|e| anyhow!("Failed to disconnect: {}", e))?;
+        if let Some(jump_chain) = &self.jump_chain {
+            jump_chain.close().await;
+        }
         Ok(())
     }
+
+    /// 获取共享的连接 Handle
+    pub fn transport(&self) -> SharedHandle {
+        self.handle.clone()
+    }
+}
+
+/// 建立到 SSH 服务器的传输连接（未认证）
+pub async fn connect(
+    config: Arc<Config>,
+    host: &str,
+    port: u16,
+    jump_host: Option<&JumpHostConfig>,
+    handler: SSHClientHandler,
+    session_id: &str,
+    app_handle: Option<tauri::AppHandle>,
+) -> Result<(Handle<SSHClientHandler>, Option<JumpChain>)> {
+    match jump_host {
+        Some(jump_host) => {
+            let (jump_chain, stream) = JumpChain::connect(
+                config.clone(),
+                jump_host,
+                host,
+                port,
+                session_id,
+                app_handle,
+            )
+            .await?;
+            let handle = client::connect_stream(config, stream, handler)
+                .await
+                .map_err(|e| anyhow!("Failed to connect to SSH server through jump host: {}", e))?;
+            Ok((handle, Some(jump_chain)))
+        }
+        None => {
+            let handle = client::connect(config, (host, port), handler)
+                .await
+                .map_err(|e| anyhow!("Failed to connect to SSH server: {}", e))?;
+            Ok((handle, None))
+        }
+    }
+}
+
+/// 连接、认证并打开带 PTY 的 shell channel
+async fn open_shell(
+    params: &ConnectParams,
+    options: &SessionOptions,
+    session_id: &str,
+    app_handle: tauri::AppHandle,
+    term_size: TermSize,
+) -> Result<(Handle<SSHClientHandler>, Option<JumpChain>, Channel<Msg>)> {
+    // 创建客户端配置
+    let config = Arc::new(options.client_config());
+
+    // 创建 Handler
+    let handler = SSHClientHandler::new(
+        session_id,
+        &params.host,
+        params.port,
+        Some(app_handle.clone()),
+    )
+    .with_agent_forwarding(options.agent_forwarding);
+
+    // 连接到 SSH 服务器（如配置了跳板机则经由跳板机链）
+    let (mut handle, jump_chain) = connect(
+        config,
+        &params.host,
+        params.port,
+        params.jump_host.as_ref(),
+        handler,
+        session_id,
+        Some(app_handle),
+    )
+    .await?;
+
+    // 进行认证
+    authenticate(
+        &mut handle,
+        &params.username,
+        &params.auth_method,
+        params.password.as_deref(),
+        params.key_path.as_deref(),
+        params.passphrase.as_deref(),
+        params.cert_path.as_deref(),
+        session_id,
+        Some(&app_handle),
+    )
+    .await?;
+
+    let channel = open_shell_channel(&handle, term_size, options.agent_forwarding).await?;
+    Ok((handle, jump_chain, channel))
+}
+
+/// 在已认证的连接上打开带 PTY 的 shell channel
+async fn open_shell_channel(
+    handle: &Handle<SSHClientHandler>,
+    term_size: TermSize,
+    agent_forwarding: bool,
+) -> Result<Channel<Msg>> {
+    // 打开 session channel
+    let channel = handle
+        .channel_open_session()
+        .await
+        .map_err(|e| anyhow!("Failed to open session channel: {}", e))?;
+
+    // 请求 PTY
+    channel
+        .request_pty(
+            false,
+            "xterm-256color",
+            term_size.cols,
+            term_size.rows,
+            term_size.width,
+            term_size.height,
+            &[],
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to request PTY: {}", e))?;
+
+    // 请求 agent 转发
+    if agent_forwarding {
+        channel
+            .agent_forward(false)
+            .await
+            .map_err(|e| anyhow!("Failed to request agent forwarding: {}", e))?;
+    }
+
+    // 请求 shell
+    channel
+        .request_shell(false)
+        .await
+        .map_err(|e| anyhow!("Failed to request shell: {}", e))?;
+
+    Ok(channel)
+}
+
+/// 经由连接池重连共享连接，并在其上重新打开 shell channel
+async fn reopen_pooled_shell(
+    connection_id: &str,
+    options: &SessionOptions,
+    app_handle: &tauri::AppHandle,
+    term_size: TermSize,
+) -> Result<(u64, Channel<Msg>)> {
+    let (transport, generation) = get_connection_pool()
+        .reconnect(connection_id, Some(app_handle.clone()))
+        .await?;
+    let handle = transport.read().await;
+    let channel = open_shell_channel(&handle, term_size, options.agent_forwarding).await?;
+    Ok((generation, channel))
+}
+
+/// 会话数据读取任务
+/// - 输出合并后通过 ssh-data-{id} 事件推送 { offset, data }（达到 flush_bytes 或停留 flush_interval_ms 后发送）
+/// - 前端未确认的输出超过高水位时暂停读取 channel，直到前端通过 ssh_ack_output 确认（见 FlowControl）
+/// - 输出按触发规则匹配，命中时执行规则的动作
+/// - channel 关闭时交给会话管理器处理（清理或自动重连）
+#[allow(clippy::too_many_arguments)]
+async fn read_loop(
+    session_id: String,
+    connection_id: Option<String>,
+    channel: Arc<Mutex<Channel<Msg>>>,
+    flow: Arc<FlowControl>,
+    activity: Arc<SessionActivity>,
+    scrollback: Arc<Scrollback>,
+    shutdown: Arc<Notify>,
+    options: OutputOptions,
+    app_handle: tauri::AppHandle,
+) {
+    if let Err(e) = get_trigger_manager().ensure_loaded().await {
+        log::warn!("Failed to load output triggers: {}", e);
+    }
+    let mut matcher = TriggerMatcher::new(connection_id);
+    let mut batcher = OutputBatcher::new(&options);
+    let flush = |data: Vec<u8>| output::emit(&app_handle, &session_id, &flow, &scrollback, &data);
+    loop {
+        // 前端未确认的输出过多时停止读取，直到前端确认（或会话关闭）
+        if flow.is_paused() {
+            flush(batcher.take());
+            flow.resumed().await;
+            if flow.is_closed() {
+                let _ = channel.lock().await.close().await;
+                break;
+            }
+        }
+
+        let msg = {
+            let mut ch = channel.lock().await;
+            let wait = async {
+                match batcher.deadline() {
+                    Some(deadline) => tokio::time::timeout_at(deadline.into(), ch.wait()).await,
+                    None => Ok(ch.wait().await),
+                }
+            };
+            let received = tokio::select! {
+                msg = wait => Some(msg),
+                _ = shutdown.notified() => None,
+            };
+            match received {
+                Some(msg) => msg,
+                // 会话被主动关闭
+                None => {
+                    let _ = ch.close().await;
+                    break;
+                }
+            }
+        };
+        match msg {
+            // 到达发送时间
+            Err(_) => flush(batcher.take()),
+            Ok(Some(ChannelMsg::Data { ref data }))
+            | Ok(Some(ChannelMsg::ExtendedData { ref data, .. })) => {
+                activity.record_in(data.len());
+                get_recording_manager().record_output(&session_id, data);
+                let hits = matcher.feed(data);
+                if !hits.is_empty() {
+                    triggers::fire(&session_id, hits, &app_handle).await;
+                }
+                if batcher.push(data) {
+                    flush(batcher.take());
+                }
+            }
+            Ok(Some(ChannelMsg::Eof)) | Ok(Some(ChannelMsg::Close)) | Ok(None) => {
+                flush(batcher.take());
+                if !get_ssh_manager()
+                    .handle_disconnect(&session_id, &app_handle)
+                    .await
+                {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 从数据库读取已保存的 SSH 连接配置
+pub async fn load_ssh_config(connection_id: &str) -> Result<SSHConfig> {
+    let db = get_db();
+
+    let (connection_type, config) =
+        sqlx::query_as::<_, (String, String)>("SELECT type, config FROM connections WHERE id = ?")
+            .bind(connection_id)
+            .fetch_optional(db.pool())
+            .await
+            .map_err(|e| anyhow!("Failed to load connection: {}", e))?
+            .ok_or_else(|| anyhow!("Connection not found: {}", connection_id))?;
+
+    if connection_type != "ssh" {
+        return Err(anyhow!(
+            "Connection {} is not an SSH connection",
+            connection_id
+        ));
+    }
+
+    serde_json::from_str(&config).map_err(|e| anyhow!("Invalid SSH config: {}", e))
+}
+
+/// 使用指定的认证方式进行认证
+/// - key 认证时提供了 cert_path 则使用 OpenSSH 证书认证
+/// - session_id / app_handle 用于 keyboard-interactive 认证时向前端发送提示
+#[allow(clippy::too_many_arguments)]
+pub async fn authenticate(
+    handle: &mut Handle<SSHClientHandler>,
+    username: &str,
+    auth_method: &str,
+    password: Option<&str>,
+    key_path: Option<&str>,
+    passphrase: Option<&str>,
+    cert_path: Option<&str>,
+    session_id: &str,
+    app_handle: Option<&tauri::AppHandle>,
+) -> Result<()> {
+    let authenticated = match auth_method {
+        "password" => {
+            let pwd = password.ok_or_else(|| anyhow!("Password not provided"))?;
+            handle
+                .authenticate_password(username, pwd)
+                .await
+                .map_err(|e| anyhow!("Password authentication failed: {}", e))?
+        }
+        "key" => {
+            let key_file = key_path.ok_or_else(|| anyhow!("Key path not provided"))?;
+            let key_pair = if let Some(pass) = passphrase {
+                russh_keys::load_secret_key(key_file, Some(pass))
+                    .map_err(|e| anyhow!("Failed to load key with passphrase: {}", e))?
+            } else {
+                russh_keys::load_secret_key(key_file, None)
+                    .map_err(|e| anyhow!("Failed to load key: {}", e))?
+            };
+
+            match cert_path {
+                Some(cert_path) => {
+                    let certificate = cert::load(cert_path)?;
+                    cert::ensure_valid(&certificate)?;
+                    handle
+                        .authenticate_openssh_cert(username, Arc::new(key_pair), certificate)
+                        .await
+                        .map_err(|e| anyhow!("Certificate authentication failed: {}", e))?
+                }
+                None => handle
+                    .authenticate_publickey(username, Arc::new(key_pair))
+                    .await
+                    .map_err(|e| anyhow!("Public key authentication failed: {}", e))?,
+            }
+        }
+        "keyboard-interactive" => {
+            auth_prompt::authenticate(handle, username, password, session_id, app_handle).await?
+        }
+        "agent" => agent::authenticate(handle, username)
+            .await
+            .map_err(|e| anyhow!("Agent authentication failed: {}", e))?,
+        _ => return Err(anyhow!("Unsupported auth method: {}", auth_method)),
+    };
+
+    if !authenticated {
+        return Err(anyhow!("Authentication failed"));
+    }
+
+    Ok(())
+}
+
+/// 输入的写入目标：会话在广播集合中时为整个集合，否则只有该会话
+fn broadcast_targets(broadcast: Vec<String>, session_id: &str) -> Vec<String> {
+    if broadcast.iter().any(|id| id == session_id) {
+        broadcast
+    } else {
+        vec![session_id.to_string()]
+    }
+}
+
+/// 去掉重复的会话 ID，保持原有顺序
+fn unique_session_ids(session_ids: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(session_ids.len());
+    for id in session_ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
+
+/// 并发写入各会话，按传入顺序返回每个会话的结果
+/// - 单个会话写入缓慢或卡住时不会阻塞其它会话的输入
+async fn write_concurrently<T, F, Fut>(
+    targets: Vec<(String, Option<T>)>,
+    write: F,
+) -> Vec<WriteResult>
+where
+    F: Fn(String, T) -> Fut,
+    Fut: Future<Output = std::result::Result<(), String>>,
+{
+    let write = &write;
+    join_all(targets.into_iter().map(|(session_id, target)| async move {
+        let outcome = match target {
+            Some(target) => write(session_id.clone(), target).await,
+            None => Err(format!("Session not found: {}", session_id)),
+        };
+        WriteResult {
+            session_id,
+            success: outcome.is_ok(),
+            error: outcome.err(),
+        }
+    }))
+    .await
 }
 
 /// SSH 会话管理器
 pub struct SSHSessionManager {
     sessions: Arc<Mutex<HashMap<String, SSHSessionHandle>>>,
     app_handle: Arc<Mutex<Option<tauri::AppHandle>>>,
+    /// 广播模式下同步输入的会话集合（为空表示未开启广播）
+    broadcast: Arc<Mutex<Vec<String>>>,
+}
+
+/// 批量写入时从会话表中取出的写入目标（连接 Handle、channel、统计）
+type WriteTarget = (SharedHandle, ChannelId, Arc<SessionActivity>);
+
+/// 单个会话的写入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteResult {
+    pub session_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 impl SSHSessionManager {
@@ -81,6 +1000,7 @@ impl SSHSessionManager {
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
             app_handle: Arc::new(Mutex::new(None)),
+            broadcast: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -89,9 +1009,22 @@ impl SSHSessionManager {
         *guard = Some(handle);
     }
 
+    /// 获取 AppHandle（用于向前端发送事件）
+    pub async fn app_handle(&self) -> Result<tauri::AppHandle> {
+        self.app_handle
+            .lock()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| anyhow!("App handle not set"))
+    }
+
     /// 创建新的 SSH 会话
+    /// - connection_id 为打开会话时使用的已保存连接（仅用于会话列表展示）
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_session(
         &self,
+        connection_id: Option<String>,
         host: String,
         port: u16,
         username: String,
@@ -99,98 +1032,117 @@ impl SSHSessionManager {
         password: Option<&str>,
         key_path: Option<&str>,
         passphrase: Option<&str>,
+        cert_path: Option<&str>,
+        jump_host: Option<&JumpHostConfig>,
+        options: SessionOptions,
     ) -> Result<String> {
         let session_id = Uuid::new_v4().to_string();
-        let app_handle = self
-            .app_handle
-            .lock()
-            .await
-            .as_ref()
-            .ok_or_else(|| anyhow!("App handle not set"))?
-            .clone();
+        let app_handle = self.app_handle().await?;
 
-        // 创建客户端配置
-        let config = Arc::new(Config::default());
+        let params = ConnectParams {
+            host,
+            port,
+            username,
+            auth_method: auth_method.to_string(),
+            password: password.map(str::to_string),
+            key_path: key_path.map(str::to_string),
+            passphrase: passphrase.map(str::to_string),
+            cert_path: cert_path.map(str::to_string),
+            jump_host: jump_host.cloned(),
+        };
+        let term_size = TermSize::default();
 
-        // 创建 Handler
-        let handler = SSHClientHandler;
+        let (handle, jump_chain, channel) = open_shell(
+            &params,
+            &options,
+            &session_id,
+            app_handle.clone(),
+            term_size,
+        )
+        .await?;
 
-        // 连接到 SSH 服务器
-        let mut handle = client::connect(config, (host.as_str(), port), handler)
-            .await
-            .map_err(|e| anyhow!("Failed to connect to SSH server: {}", e))?;
+        let session = SSHSessionHandle::new(
+            session_id,
+            connection_id,
+            Arc::new(RwLock::new(handle)),
+            jump_chain,
+            None,
+            channel,
+            term_size,
+            params,
+            options,
+        );
+        Ok(self.start_session(session, app_handle).await)
+    }
 
-        // 进行认证
-        let authenticated = match auth_method {
-            "password" => {
-                let pwd = password.ok_or_else(|| anyhow!("Password not provided"))?;
-                handle
-                    .authenticate_password(&username, pwd)
-                    .await
-                    .map_err(|e| anyhow!("Password authentication failed: {}", e))?
-            }
-            "key" => {
-                let key_file = key_path.ok_or_else(|| anyhow!("Key path not provided"))?;
-                let key_pair = if let Some(pass) = passphrase {
-                    russh_keys::load_secret_key(key_file, Some(pass))
-                        .map_err(|e| anyhow!("Failed to load key with passphrase: {}", e))?
-                } else {
-                    russh_keys::load_secret_key(key_file, None)
-                        .map_err(|e| anyhow!("Failed to load key: {}", e))?
-                };
+    /// 在已保存连接的共享连接上打开新的终端会话
+    /// - 同一连接的多个终端只认证一次（OTP 等只需输入一次）
+    pub async fn create_pooled_session(
+        &self,
+        connection_id: &str,
+        options: SessionOptions,
+    ) -> Result<String> {
+        let session_id = Uuid::new_v4().to_string();
+        let app_handle = self.app_handle().await?;
+        let ssh_config = load_ssh_config(connection_id).await?;
 
-                handle
-                    .authenticate_publickey(&username, Arc::new(key_pair))
-                    .await
-                    .map_err(|e| anyhow!("Public key authentication failed: {}", e))?
+        let lease = get_connection_pool()
+            .acquire(connection_id, Some(&options), Some(app_handle.clone()))
+            .await?;
+        let term_size = TermSize::default();
+
+        let transport = lease.transport();
+        let channel = {
+            let handle = transport.read().await;
+            open_shell_channel(&handle, term_size, options.agent_forwarding).await
+        };
+        let channel = match channel {
+            Ok(channel) => channel,
+            Err(e) => {
+                lease.release().await;
+                return Err(e);
             }
-            _ => return Err(anyhow!("Unsupported auth method: {}", auth_method)),
         };
 
-        if !authenticated {
-            return Err(anyhow!("Authentication failed"));
-        }
-
-        // 打开 session channel
-        let channel = handle
-            .channel_open_session()
-            .await
-            .map_err(|e| anyhow!("Failed to open session channel: {}", e))?;
-
-        // 请求 PTY
-        channel
-            .request_pty(
-                false,
-                "xterm-256color",
-                80,  // 列数
-                24,  // 行数
-                640, // 像素宽度
-                480, // 像素高度
-                &[],
-            )
-            .await
-            .map_err(|e| anyhow!("Failed to request PTY: {}", e))?;
-
-        // 请求 shell
-        channel
-            .request_shell(false)
-            .await
-            .map_err(|e| anyhow!("Failed to request shell: {}", e))?;
-
-        // 用 Arc<Mutex> 共享 Channel：读取任务用 wait()，resize 用 window_change()
-        let channel_id = channel.id();
-        let shared_channel = Arc::new(Mutex::new(channel));
+        let session = SSHSessionHandle::new(
+            session_id,
+            Some(connection_id.to_string()),
+            transport,
+            None,
+            Some(lease),
+            channel,
+            term_size,
+            ConnectParams::from(&ssh_config),
+            options,
+        );
+        Ok(self.start_session(session, app_handle).await)
+    }
 
-        // 创建会话句柄
-        let session = SSHSessionHandle {
-            id: session_id.clone(),
-            host: host.clone(),
-            port,
-            username: username.clone(),
-            handle,
-            channel_id,
-            channel: shared_channel.clone(),
-        };
+    /// 保存会话、启动读取任务并发送 ssh-connected-{id} 事件
+    async fn start_session(
+        &self,
+        session: SSHSessionHandle,
+        app_handle: tauri::AppHandle,
+    ) -> String {
+        let session_id = session.id.clone();
+        let payload = json!({
+            "session_id": session_id,
+            "connection_id": session.connection_id,
+            "host": session.host,
+            "port": session.port,
+            "username": session.username,
+        });
+        let reader = read_loop(
+            session_id.clone(),
+            session.connection_id.clone(),
+            session.channel.clone(),
+            session.flow.clone(),
+            session.activity.clone(),
+            session.scrollback.clone(),
+            session.shutdown.clone(),
+            session.options.output.clone(),
+            app_handle.clone(),
+        );
 
         // 保存会话
         self.sessions
@@ -199,53 +1151,200 @@ impl SSHSessionManager {
             .insert(session_id.clone(), session);
 
         // 启动数据读取任务
-        let session_id_clone = session_id.clone();
-        let app_handle_clone = app_handle.clone();
-        let read_channel = shared_channel;
+        tokio::spawn(reader);
 
-        tokio::spawn(async move {
-            loop {
-                let msg = {
-                    let mut ch = read_channel.lock().await;
-                    ch.wait().await
-                };
-                match msg {
-                    Some(ChannelMsg::Data { ref data }) => {
-                        let encoded =
-                            base64::engine::general_purpose::STANDARD.encode(&data[..]);
-                        let _ = app_handle_clone
-                            .emit_all(&format!("ssh-data-{}", session_id_clone), encoded);
-                    }
-                    Some(ChannelMsg::ExtendedData { ref data, .. }) => {
-                        let encoded =
-                            base64::engine::general_purpose::STANDARD.encode(&data[..]);
-                        let _ = app_handle_clone
-                            .emit_all(&format!("ssh-data-{}", session_id_clone), encoded);
-                    }
-                    Some(ChannelMsg::Eof) | Some(ChannelMsg::Close) | None => {
-                        let _ = app_handle_clone.emit_all(
-                            &format!("ssh-disconnected-{}", session_id_clone),
-                            json!({ "session_id": session_id_clone }),
-                        );
-                        break;
-                    }
-                    _ => {}
+        // 发送连接成功事件
+        let _ = app_handle.emit_all(&format!("ssh-connected-{}", session_id), payload);
+
+        session_id
+    }
+
+    /// 处理会话的 channel 关闭
+    /// - 连接仍然存活（shell 正常退出）时清理会话
+    /// - 连接意外断开（共享连接已被其他会话重连也视为断开）且开启了自动重连时按退避策略重连，重连成功返回 true
+    async fn handle_disconnect(&self, session_id: &str, app_handle: &tauri::AppHandle) -> bool {
+        let state = {
+            let sessions = self.sessions.lock().await;
+            match sessions.get(session_id) {
+                // 会话已被主动断开
+                None => return false,
+                Some(session) => (
+                    session.handle.read().await.is_closed(),
+                    session
+                        .lease
+                        .as_ref()
+                        .map(|lease| lease.connection_id().to_string()),
+                    session.transport_generation,
+                    session.params.clone(),
+                    session.options.clone(),
+                    session.term_size().await,
+                    session.activity.clone(),
+                ),
+            }
+        };
+        let (transport_closed, pooled, generation, params, options, term_size, activity) = state;
+
+        let transport_lost = transport_closed
+            || match &pooled {
+                Some(connection_id) => {
+                    get_connection_pool().generation(connection_id).await != Some(generation)
                 }
+                None => false,
+            };
+
+        if transport_lost && options.auto_reconnect {
+            activity.reconnecting.store(true, Ordering::Relaxed);
+            let reconnected = self
+                .reconnect(
+                    session_id,
+                    pooled.as_deref(),
+                    &params,
+                    &options,
+                    term_size,
+                    app_handle,
+                )
+                .await;
+            activity.reconnecting.store(false, Ordering::Relaxed);
+            if reconnected {
+                activity.reconnects.fetch_add(1, Ordering::Relaxed);
+                activity.touch();
+                return true;
             }
-        });
+        }
 
-        // 发送连接成功事件
         let _ = app_handle.emit_all(
-            &format!("ssh-connected-{}", session_id),
-            json!({
-                "session_id": session_id,
-                "host": host,
-                "port": port,
-                "username": username,
-            }),
+            &format!("ssh-disconnected-{}", session_id),
+            json!({ "session_id": session_id }),
         );
+        let _ = self.remove_session(session_id).await;
+        false
+    }
+
+    /// 重新连接会话，保持会话 ID 不变
+    /// - 共享连接上的会话经由连接池重连（多个会话只重连一次），再打开新的 shell channel
+    /// - 每次尝试前发送 ssh-reconnecting-{id} 事件，成功后发送 ssh-reconnected-{id} 事件
+    async fn reconnect(
+        &self,
+        session_id: &str,
+        pooled: Option<&str>,
+        params: &ConnectParams,
+        options: &SessionOptions,
+        term_size: TermSize,
+        app_handle: &tauri::AppHandle,
+    ) -> bool {
+        let mut last_error: Option<String> = None;
+
+        for attempt in 1..=options.max_reconnect_attempts {
+            let delay = options.reconnect_delay(attempt);
+            let _ = app_handle.emit_all(
+                &format!("ssh-reconnecting-{}", session_id),
+                json!({
+                    "session_id": session_id,
+                    "attempt": attempt,
+                    "max_attempts": options.max_reconnect_attempts,
+                    "delay_ms": delay.as_millis() as u64,
+                    "last_error": last_error,
+                }),
+            );
+            tokio::time::sleep(delay).await;
+
+            // 等待期间会话可能已被主动断开
+            if !self.sessions.lock().await.contains_key(session_id) {
+                return false;
+            }
+
+            let reopened = match pooled {
+                Some(connection_id) => {
+                    reopen_pooled_shell(connection_id, options, app_handle, term_size)
+                        .await
+                        .map(|(generation, channel)| (Transport::Pooled(generation), channel))
+                }
+                None => open_shell(params, options, session_id, app_handle.clone(), term_size)
+                    .await
+                    .map(|(handle, jump_chain, channel)| {
+                        (Transport::Owned(handle, jump_chain), channel)
+                    }),
+            };
+
+            match reopened {
+                Ok((transport, channel)) => {
+                    if !self.replace_transport(session_id, transport, channel).await {
+                        return false;
+                    }
 
-        Ok(session_id)
+                    // 旧连接上的 SFTP 子系统和远程端口转发已失效
+                    get_sftp_manager().discard_session(session_id).await;
+                    get_forward_manager()
+                        .restore_remote_forwards(session_id)
+                        .await;
+
+                    let _ = app_handle.emit_all(
+                        &format!("ssh-reconnected-{}", session_id),
+                        json!({ "session_id": session_id, "attempt": attempt }),
+                    );
+                    return true;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Reconnect attempt {}/{} for session {} failed: {}",
+                        attempt,
+                        options.max_reconnect_attempts,
+                        session_id,
+                        e
+                    );
+                    last_error = Some(e.to_string());
+                }
+            }
+        }
+
+        false
+    }
+
+    /// 用重新建立的连接替换会话的 Handle 和 Channel
+    async fn replace_transport(
+        &self,
+        session_id: &str,
+        transport: Transport,
+        channel: Channel<Msg>,
+    ) -> bool {
+        let mut sessions = self.sessions.lock().await;
+        let Some(session) = sessions.get_mut(session_id) else {
+            drop(sessions);
+            match transport {
+                Transport::Owned(handle, jump_chain) => {
+                    let _ = handle
+                        .disconnect(Disconnect::ByApplication, "", "English")
+                        .await;
+                    if let Some(jump_chain) = jump_chain {
+                        jump_chain.close().await;
+                    }
+                }
+                Transport::Pooled(_) => {
+                    let _ = channel.close().await;
+                }
+            }
+            return false;
+        };
+
+        let old_jump_chain = match transport {
+            Transport::Owned(handle, jump_chain) => {
+                *session.handle.write().await = handle;
+                std::mem::replace(&mut session.jump_chain, jump_chain)
+            }
+            // 共享连接的 Handle 已由连接池替换
+            Transport::Pooled(generation) => {
+                session.transport_generation = generation;
+                None
+            }
+        };
+        session.channel_id = channel.id();
+        *session.channel.lock().await = channel;
+        drop(sessions);
+
+        if let Some(jump_chain) = old_jump_chain {
+            jump_chain.close().await;
+        }
+        true
     }
 
     /// 写入数据到会话
@@ -255,7 +1354,66 @@ impl SSHSessionManager {
             .get(session_id)
             .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
 
-        session.write(data).await
+        session.write(data).await?;
+        session.activity.record_out(data.len());
+        get_recording_manager().record_input(session_id, data);
+        Ok(())
+    }
+
+    /// 写入数据，如果会话处于广播集合中则同步写入集合内的所有会话
+    /// - 返回每个会话的写入结果，单个会话失败不影响其它会话
+    pub async fn write_with_broadcast(&self, session_id: &str, data: &[u8]) -> Vec<WriteResult> {
+        let broadcast = self.broadcast.lock().await.clone();
+        let targets = broadcast_targets(broadcast, session_id);
+
+        self.write_to_sessions(&targets, data).await
+    }
+
+    /// 向多个会话写入相同的数据
+    pub async fn write_to_sessions(&self, session_ids: &[String], data: &[u8]) -> Vec<WriteResult> {
+        // 先取出共享的 Handle，避免在写入期间一直持有会话表的锁
+        let targets: Vec<(String, Option<WriteTarget>)> = {
+            let sessions = self.sessions.lock().await;
+            session_ids
+                .iter()
+                .map(|id| {
+                    let target = sessions.get(id).map(|session| {
+                        (
+                            session.transport(),
+                            session.channel_id,
+                            session.activity.clone(),
+                        )
+                    });
+                    (id.clone(), target)
+                })
+                .collect()
+        };
+
+        write_concurrently(
+            targets,
+            |session_id, (handle, channel_id, activity)| async move {
+                handle
+                    .read()
+                    .await
+                    .data(channel_id, CryptoVec::from_slice(data))
+                    .await
+                    .map_err(|_| "Failed to write to SSH channel".to_string())?;
+                activity.record_out(data.len());
+                get_recording_manager().record_input(&session_id, data);
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// 设置广播会话集合（传入空列表关闭广播）
+    pub async fn set_broadcast(&self, session_ids: Vec<String>) {
+        *self.broadcast.lock().await = unique_session_ids(session_ids);
+    }
+
+    /// 获取广播会话集合
+    pub async fn get_broadcast(&self) -> Vec<String> {
+        self.broadcast.lock().await.clone()
     }
 
     /// 调整窗口大小
@@ -272,14 +1430,121 @@ impl SSHSessionManager {
             .get(session_id)
             .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
 
-        session.resize(cols, rows, width, height).await
+        session.resize(cols, rows, width, height).await?;
+        get_recording_manager().record_resize(session_id, cols, rows);
+        Ok(())
+    }
+
+    /// 获取会话当前的终端大小
+    pub async fn term_size(&self, session_id: &str) -> Result<TermSize> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session.term_size().await)
+    }
+
+    /// 前端确认已处理的输出字节数（流控）
+    pub async fn ack_output(&self, session_id: &str, bytes: usize) -> Result<()> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        session.flow.ack(bytes);
+        Ok(())
+    }
+
+    /// 从指定偏移量读取会话的回滚缓冲区
+    pub async fn read_scrollback(
+        &self,
+        session_id: &str,
+        offset: Option<u64>,
+        max_bytes: Option<usize>,
+    ) -> Result<ScrollbackRead> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session.scrollback.read(offset, max_bytes))
+    }
+
+    /// 重新附加到会话（前端重新加载或在新窗口中打开）
+    /// - ssh-data-{id} 事件带有输出的偏移量，前端据此去掉与回滚内容重叠的部分
+    /// - 清零流控计数，旧窗口未确认的输出不再阻塞读取
+    pub async fn attach_session(
+        &self,
+        session_id: &str,
+        offset: Option<u64>,
+        max_bytes: Option<usize>,
+    ) -> Result<ScrollbackRead> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        session.flow.reset();
+        Ok(session.scrollback.read(offset, max_bytes))
+    }
+
+    /// 获取会话的共享连接 Handle
+    pub async fn get_transport(&self, session_id: &str) -> Result<SharedHandle> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session.transport())
+    }
+
+    /// 在会话的连接上打开 direct-tcpip channel
+    pub async fn open_direct_tcpip(
+        &self,
+        session_id: &str,
+        host: &str,
+        port: u16,
+        originator_address: &str,
+        originator_port: u16,
+    ) -> Result<Channel<Msg>> {
+        let transport = self.get_transport(session_id).await?;
+        let handle = transport.read().await;
+        handle
+            .channel_open_direct_tcpip(
+                host,
+                port as u32,
+                originator_address,
+                originator_port as u32,
+            )
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to open direct-tcpip channel to {}:{}: {}",
+                    host,
+                    port,
+                    e
+                )
+            })
     }
 
     /// 删除会话
     pub async fn remove_session(&self, session_id: &str) -> Result<()> {
-        let mut sessions = self.sessions.lock().await;
-        if let Some(session) = sessions.remove(session_id) {
+        get_forward_manager()
+            .close_session_forwards(session_id)
+            .await;
+        get_sftp_manager().close_session(session_id).await;
+        get_monitor_manager().stop(session_id).await;
+        self.broadcast.lock().await.retain(|id| id != session_id);
+        let _ = get_recording_manager().stop(session_id).await;
+
+        let session = self.sessions.lock().await.remove(session_id);
+        if let Some(mut session) = session {
+            session.flow.close();
             let _ = session.close().await;
+            if let Some(lease) = session.lease.take() {
+                lease.release().await;
+            }
         }
         Ok(())
     }
@@ -288,6 +1553,55 @@ impl SSHSessionManager {
     pub async fn list_sessions(&self) -> Vec<String> {
         self.sessions.lock().await.keys().cloned().collect()
     }
+
+    /// 使用指定已保存连接的共享连接的会话
+    pub async fn sessions_on_connection(&self, connection_id: &str) -> Vec<String> {
+        self.sessions
+            .lock()
+            .await
+            .values()
+            .filter(|session| {
+                session
+                    .lease
+                    .as_ref()
+                    .is_some_and(|lease| lease.connection_id() == connection_id)
+            })
+            .map(|session| session.id.clone())
+            .collect()
+    }
+
+    /// 列出所有会话的详细信息（按连接时间排序）
+    pub async fn list_session_infos(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.lock().await;
+        let mut ordered: Vec<&SSHSessionHandle> = sessions.values().collect();
+        ordered.sort_by_key(|session| session.connected_at);
+
+        let mut infos = Vec::with_capacity(ordered.len());
+        for session in ordered {
+            infos.push(session.info().await);
+        }
+        infos
+    }
+
+    /// 获取单个会话的详细信息
+    pub async fn session_info(&self, session_id: &str) -> Result<SessionInfo> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session.info().await)
+    }
+
+    /// 获取会话的流量统计
+    pub async fn session_stats(&self, session_id: &str) -> Result<SessionStats> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session.stats())
+    }
 }
 
 /// 全局 session 管理器
@@ -296,3 +1610,105 @@ static SSH_MANAGER: Lazy<SSHSessionManager> = Lazy::new(|| SSHSessionManager::ne
 pub fn get_ssh_manager() -> &'static SSHSessionManager {
     &SSH_MANAGER
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backoff() {
+        let options = SessionOptions::default();
+        assert_eq!(options.reconnect_delay(1), Duration::from_millis(1000));
+        assert_eq!(options.reconnect_delay(2), Duration::from_millis(2000));
+        assert_eq!(options.reconnect_delay(4), Duration::from_millis(8000));
+        assert_eq!(options.reconnect_delay(10), Duration::from_millis(30_000));
+        assert_eq!(options.reconnect_delay(200), Duration::from_millis(30_000));
+    }
+
+    #[test]
+    fn test_session_options_defaults_missing_fields() {
+        let options: SessionOptions =
+            serde_json::from_str(r#"{"keepalive_interval": 0, "auto_reconnect": false}"#).unwrap();
+        assert_eq!(options.keepalive_interval, 0);
+        assert!(!options.auto_reconnect);
+        assert_eq!(options.max_reconnect_attempts, 5);
+        assert!(options.client_config().keepalive_interval.is_none());
+        assert!(options.output.flow_control);
+    }
+
+    #[test]
+    fn test_session_activity_counters() {
+        let activity = SessionActivity::new();
+        activity.record_in(120);
+        activity.record_out(3);
+        activity.record_out(4);
+        assert_eq!(activity.bytes_in.load(Ordering::Relaxed), 120);
+        assert_eq!(activity.bytes_out.load(Ordering::Relaxed), 7);
+        assert!((Utc::now() - activity.last_activity()).num_seconds() < 5);
+
+        assert_eq!(activity.status(), SessionStatus::Connected);
+        activity.reconnecting.store(true, Ordering::Relaxed);
+        assert_eq!(activity.status(), SessionStatus::Reconnecting);
+    }
+
+    #[test]
+    fn test_broadcast_targets() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        let broadcast = unique_session_ids(ids(&["a", "b", "a", "c", "b"]));
+        assert_eq!(broadcast, ids(&["a", "b", "c"]));
+
+        // 集合内的会话输入写入整个集合，集合外的只写入自己
+        assert_eq!(
+            broadcast_targets(broadcast.clone(), "b"),
+            ids(&["a", "b", "c"])
+        );
+        assert_eq!(broadcast_targets(broadcast, "d"), ids(&["d"]));
+        assert_eq!(broadcast_targets(Vec::new(), "a"), ids(&["a"]));
+    }
+
+    #[tokio::test]
+    async fn test_write_concurrently_reports_each_session() {
+        // slow 的写入要等 fast 写入后才能完成，顺序写入时会一直等待
+        let fast_done = Arc::new(Notify::new());
+        let targets = vec![
+            ("slow".to_string(), Some(fast_done.clone())),
+            ("missing".to_string(), None),
+            ("fast".to_string(), Some(fast_done.clone())),
+            ("broken".to_string(), Some(fast_done.clone())),
+        ];
+
+        let results = tokio::time::timeout(
+            Duration::from_secs(5),
+            write_concurrently(targets, |session_id, fast_done| async move {
+                match session_id.as_str() {
+                    "slow" => {
+                        fast_done.notified().await;
+                        Ok(())
+                    }
+                    "fast" => {
+                        fast_done.notify_one();
+                        Ok(())
+                    }
+                    _ => Err("Failed to write to SSH channel".to_string()),
+                }
+            }),
+        )
+        .await
+        .expect("a slow session blocked the others");
+
+        let summary: Vec<(&str, bool, Option<&str>)> = results
+            .iter()
+            .map(|r| (r.session_id.as_str(), r.success, r.error.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("slow", true, None),
+                ("missing", false, Some("Session not found: missing")),
+                ("fast", true, None),
+                ("broken", false, Some("Failed to write to SSH channel")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use super::client::{get_ssh_manager, SSHClientHandler};
+use super::pool::get_connection_pool;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use russh::client::Handle;
+use russh::{ChannelMsg, Sig};
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{Mutex, Notify, RwLock};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// 结果中最多保留的输出字节数（超出部分仍会通过事件推送）
+const MAX_CAPTURED_OUTPUT: usize = 1024 * 1024;
+
+/// 输出流
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// 命令执行的原始输出
+#[derive(Debug, Default)]
+pub struct ExecOutput {
+    pub exit_status: Option<u32>,
+    pub exit_signal: Option<String>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub truncated: bool,
+    pub timed_out: bool,
+    pub cancelled: bool,
+}
+
+impl ExecOutput {
+    fn capture(&mut self, stream: OutputStream, data: &[u8]) {
+        let buf = match stream {
+            OutputStream::Stdout => &mut self.stdout,
+            OutputStream::Stderr => &mut self.stderr,
+        };
+        let room = MAX_CAPTURED_OUTPUT.saturating_sub(buf.len());
+        if data.len() > room {
+            self.truncated = true;
+        }
+        buf.extend_from_slice(&data[..data.len().min(room)]);
+    }
+}
+
+/// 命令执行结果（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecResult {
+    pub exec_id: String,
+    pub exit_status: Option<u32>,
+    pub exit_signal: Option<String>,
+    pub stdout: String,
+    pub stderr: String,
+    pub truncated: bool,
+    pub timed_out: bool,
+    pub cancelled: bool,
+}
+
+impl ExecResult {
+    fn from_output(exec_id: &str, output: ExecOutput) -> Self {
+        Self {
+            exec_id: exec_id.to_string(),
+            exit_status: output.exit_status,
+            exit_signal: output.exit_signal,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            truncated: output.truncated,
+            timed_out: output.timed_out,
+            cancelled: output.cancelled,
+        }
+    }
+}
+
+/// 在连接上打开 exec channel 执行单条命令（不请求 PTY）
+/// - on_output 收到每一块 stdout/stderr 数据
+/// - 超时或取消时向远程进程发送 KILL 信号并关闭 channel
+pub async fn run_command(
+    transport: &RwLock<Handle<SSHClientHandler>>,
+    command: &str,
+    timeout: Option<Duration>,
+    cancel: Option<Arc<Notify>>,
+    on_output: impl FnMut(OutputStream, &[u8]),
+) -> Result<ExecOutput> {
+    run_command_with_input(transport, command, None, timeout, cancel, on_output).await
+}
+
+/// 执行命令并将 input 写入远程进程的标准输入（写完后发送 EOF）
+pub async fn run_command_with_input(
+    transport: &RwLock<Handle<SSHClientHandler>>,
+    command: &str,
+    input: Option<&[u8]>,
+    timeout: Option<Duration>,
+    cancel: Option<Arc<Notify>>,
+    mut on_output: impl FnMut(OutputStream, &[u8]),
+) -> Result<ExecOutput> {
+    let mut channel = transport
+        .read()
+        .await
+        .channel_open_session()
+        .await
+        .map_err(|e| anyhow!("Failed to open exec channel: {}", e))?;
+    channel
+        .exec(true, command)
+        .await
+        .map_err(|e| anyhow!("Failed to execute command: {}", e))?;
+
+    if let Some(input) = input {
+        channel
+            .data(input)
+            .await
+            .map_err(|e| anyhow!("Failed to write command input: {}", e))?;
+        channel
+            .eof()
+            .await
+            .map_err(|e| anyhow!("Failed to write command input: {}", e))?;
+    }
+
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let mut output = ExecOutput::default();
+
+    loop {
+        let msg = tokio::select! {
+            msg = channel.wait() => msg,
+            _ = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                output.timed_out = true;
+                break;
+            }
+            _ = async {
+                match &cancel {
+                    Some(cancel) => cancel.notified().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                output.cancelled = true;
+                break;
+            }
+        };
+
+        match msg {
+            Some(ChannelMsg::Data { ref data }) => {
+                on_output(OutputStream::Stdout, data);
+                output.capture(OutputStream::Stdout, data);
+            }
+            Some(ChannelMsg::ExtendedData { ref data, ext }) if ext == 1 => {
+                on_output(OutputStream::Stderr, data);
+                output.capture(OutputStream::Stderr, data);
+            }
+            Some(ChannelMsg::ExitStatus { exit_status }) => {
+                output.exit_status = Some(exit_status);
+            }
+            Some(ChannelMsg::ExitSignal { signal_name, .. }) => {
+                output.exit_signal = Some(signal_label(&signal_name));
+            }
+            Some(ChannelMsg::Close) | None => break,
+            _ => {}
+        }
+    }
+
+    if output.timed_out || output.cancelled {
+        let _ = channel.signal(Sig::KILL).await;
+        let _ = channel.close().await;
+    }
+
+    Ok(output)
+}
+
+fn signal_label(signal: &Sig) -> String {
+    match signal {
+        Sig::Custom(name) => name.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// 命令执行管理器
+pub struct ExecManager {
+    running: Mutex<HashMap<String, Arc<Notify>>>,
+}
+
+impl ExecManager {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 在已有会话上执行命令
+    pub async fn exec_on_session(
+        &self,
+        exec_id: Option<String>,
+        session_id: &str,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult> {
+        let transport = get_ssh_manager().get_transport(session_id).await?;
+        self.exec(exec_id, &transport, command, timeout).await
+    }
+
+    /// 使用已保存的连接执行命令（复用连接池中的连接，没有时建立连接，最后一个使用者释放后断开）
+    pub async fn exec_on_connection(
+        &self,
+        exec_id: Option<String>,
+        connection_id: &str,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult> {
+        let app_handle = get_ssh_manager().app_handle().await.ok();
+        let lease = get_connection_pool()
+            .acquire(connection_id, None, app_handle)
+            .await?;
+
+        let result = self
+            .exec(exec_id, &lease.transport(), command, timeout)
+            .await;
+        lease.release().await;
+
+        result
+    }
+
+    /// 取消正在执行的命令
+    pub async fn cancel(&self, exec_id: &str) -> Result<()> {
+        let running = self.running.lock().await;
+        let cancel = running
+            .get(exec_id)
+            .ok_or_else(|| anyhow!("Command not running: {}", exec_id))?;
+
+        cancel.notify_one();
+        Ok(())
+    }
+
+    /// 登记正在执行的命令，返回取消通知
+    /// - 前端可以指定 exec_id，同一 id 的命令仍在执行时拒绝，避免覆盖其取消句柄
+    async fn register(&self, exec_id: &str) -> Result<Arc<Notify>> {
+        match self.running.lock().await.entry(exec_id.to_string()) {
+            Entry::Occupied(_) => Err(anyhow!("Command already running: {}", exec_id)),
+            Entry::Vacant(entry) => Ok(entry.insert(Arc::new(Notify::new())).clone()),
+        }
+    }
+
+    /// 执行命令，stdout/stderr 通过 ssh-exec-stdout-{id} / ssh-exec-stderr-{id} 事件推送（Base64）
+    async fn exec(
+        &self,
+        exec_id: Option<String>,
+        transport: &RwLock<Handle<SSHClientHandler>>,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult> {
+        let exec_id = exec_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let app_handle = get_ssh_manager().app_handle().await.ok();
+        let cancel = self.register(&exec_id).await?;
+
+        let stdout_event = format!("ssh-exec-stdout-{}", exec_id);
+        let stderr_event = format!("ssh-exec-stderr-{}", exec_id);
+        let result = run_command(transport, command, timeout, Some(cancel), |stream, data| {
+            if let Some(app_handle) = &app_handle {
+                let event = match stream {
+                    OutputStream::Stdout => &stdout_event,
+                    OutputStream::Stderr => &stderr_event,
+                };
+                let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+                let _ = app_handle.emit_all(event, encoded);
+            }
+        })
+        .await;
+
+        self.running.lock().await.remove(&exec_id);
+
+        result.map(|output| ExecResult::from_output(&exec_id, output))
+    }
+}
+
+/// 全局命令执行管理器
+static EXEC_MANAGER: Lazy<ExecManager> = Lazy::new(ExecManager::new);
+
+pub fn get_exec_manager() -> &'static ExecManager {
+    &EXEC_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_truncates_large_output() {
+        let mut output = ExecOutput::default();
+        output.capture(OutputStream::Stdout, &vec![b'a'; MAX_CAPTURED_OUTPUT - 2]);
+        assert!(!output.truncated);
+
+        output.capture(OutputStream::Stdout, b"abcd");
+        assert!(output.truncated);
+        assert_eq!(output.stdout.len(), MAX_CAPTURED_OUTPUT);
+        assert!(output.stdout.ends_with(b"ab"));
+
+        output.capture(OutputStream::Stderr, b"err");
+        assert_eq!(output.stderr, b"err");
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_running_exec_id() {
+        let manager = ExecManager::new();
+        let cancel = manager.register("build").await.unwrap();
+        assert!(manager.register("build").await.is_err());
+
+        // 仍然取消的是第一条命令
+        manager.cancel("build").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), cancel.notified())
+            .await
+            .unwrap();
+
+        manager.running.lock().await.remove("build");
+        assert!(manager.register("build").await.is_ok());
+    }
+
+    #[test]
+    fn test_signal_label() {
+        assert_eq!(signal_label(&Sig::TERM), "TERM");
+        assert_eq!(signal_label(&Sig::Custom("USR1".to_string())), "USR1");
+    }
+}
@@ -0,0 +1,704 @@
+use super::client::get_ssh_manager;
+use super::socks;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use russh::client::Msg;
+use russh::Channel;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::task::{JoinHandle, JoinSet};
+use uuid::Uuid;
+
+/// accept 失败后的初始等待时间
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(50);
+
+/// accept 持续失败（如文件描述符耗尽）时的最长等待时间
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(2);
+
+/// 端口转发类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardKind {
+    Local,
+    Remote,
+    Dynamic,
+}
+
+/// 端口转发流量统计
+#[derive(Debug, Default)]
+pub struct ForwardStats {
+    pub bytes_sent: AtomicU64,
+    pub bytes_received: AtomicU64,
+    pub active_connections: AtomicU64,
+    pub total_connections: AtomicU64,
+}
+
+/// 端口转发信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct PortForwardInfo {
+    pub id: String,
+    pub session_id: String,
+    pub kind: ForwardKind,
+    pub bind_address: String,
+    pub bind_port: u16,
+    pub target_host: String,
+    pub target_port: u16,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub active_connections: u64,
+    pub total_connections: u64,
+    pub created_at: String,
+}
+
+/// 已打开的端口转发
+/// - Local: bind 为本地监听地址，target 为远端目标地址，task 为本地监听任务
+/// - Remote: bind 为服务器上的监听地址，target 为本地目标地址，无监听任务，隧道任务保存在 connections 中
+/// - Dynamic: bind 为本地 SOCKS5 监听地址，目标由客户端请求决定（target 为空）
+/// - 监听任务持有各连接的隧道任务，关闭转发（中止监听任务或丢弃 connections）时隧道随之中止
+struct PortForward {
+    id: String,
+    session_id: String,
+    kind: ForwardKind,
+    bind_address: String,
+    bind_port: u16,
+    target_host: String,
+    target_port: u16,
+    created_at: String,
+    stats: Arc<ForwardStats>,
+    task: Option<JoinHandle<()>>,
+    connections: JoinSet<()>,
+}
+
+impl PortForward {
+    fn info(&self) -> PortForwardInfo {
+        PortForwardInfo {
+            id: self.id.clone(),
+            session_id: self.session_id.clone(),
+            kind: self.kind,
+            bind_address: self.bind_address.clone(),
+            bind_port: self.bind_port,
+            target_host: self.target_host.clone(),
+            target_port: self.target_port,
+            bytes_sent: self.stats.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.stats.bytes_received.load(Ordering::Relaxed),
+            active_connections: self.stats.active_connections.load(Ordering::Relaxed),
+            total_connections: self.stats.total_connections.load(Ordering::Relaxed),
+            created_at: self.created_at.clone(),
+        }
+    }
+}
+
+/// 端口转发管理器
+pub struct PortForwardManager {
+    forwards: Mutex<HashMap<String, PortForward>>,
+}
+
+impl PortForwardManager {
+    pub fn new() -> Self {
+        Self {
+            forwards: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 打开本地端口转发（ssh -L）
+    /// - 在本地监听 bind_address:bind_port，每个连接通过会话的 direct-tcpip channel 转发到 target_host:target_port
+    pub async fn open_local(
+        &self,
+        session_id: &str,
+        bind_address: &str,
+        bind_port: u16,
+        target_host: &str,
+        target_port: u16,
+    ) -> Result<PortForwardInfo> {
+        // 确认会话存在
+        get_ssh_manager().get_transport(session_id).await?;
+
+        let listener = TcpListener::bind((bind_address, bind_port))
+            .await
+            .map_err(|e| anyhow!("Failed to bind {}:{}: {}", bind_address, bind_port, e))?;
+        let bind_port = listener
+            .local_addr()
+            .map_err(|e| anyhow!("Failed to get local address: {}", e))?
+            .port();
+
+        let stats = Arc::new(ForwardStats::default());
+        let session = session_id.to_string();
+        let target = target_host.to_string();
+        let task = tokio::spawn(accept_local(listener, stats.clone(), move |peer| {
+            let session = session.clone();
+            let target = target.clone();
+            async move {
+                let channel = get_ssh_manager()
+                    .open_direct_tcpip(
+                        &session,
+                        &target,
+                        target_port,
+                        &peer.ip().to_string(),
+                        peer.port(),
+                    )
+                    .await?;
+                Ok(channel.into_stream())
+            }
+        }));
+
+        let forward = PortForward {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            kind: ForwardKind::Local,
+            bind_address: bind_address.to_string(),
+            bind_port,
+            target_host: target_host.to_string(),
+            target_port,
+            created_at: Utc::now().to_rfc3339(),
+            stats,
+            task: Some(task),
+            connections: JoinSet::new(),
+        };
+        let info = forward.info();
+
+        self.forwards
+            .lock()
+            .await
+            .insert(forward.id.clone(), forward);
+
+        Ok(info)
+    }
+
+    /// 打开动态端口转发（ssh -D）
+    /// - 在本地监听 SOCKS5 代理，每个 CONNECT 请求通过会话的 direct-tcpip channel 转发
+    pub async fn open_dynamic(
+        &self,
+        session_id: &str,
+        bind_address: &str,
+        bind_port: u16,
+    ) -> Result<PortForwardInfo> {
+        // 确认会话存在
+        get_ssh_manager().get_transport(session_id).await?;
+
+        let listener = TcpListener::bind((bind_address, bind_port))
+            .await
+            .map_err(|e| anyhow!("Failed to bind {}:{}: {}", bind_address, bind_port, e))?;
+        let bind_port = listener
+            .local_addr()
+            .map_err(|e| anyhow!("Failed to get local address: {}", e))?
+            .port();
+
+        let stats = Arc::new(ForwardStats::default());
+        let task = tokio::spawn(accept_dynamic(
+            listener,
+            session_id.to_string(),
+            stats.clone(),
+        ));
+
+        let forward = PortForward {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            kind: ForwardKind::Dynamic,
+            bind_address: bind_address.to_string(),
+            bind_port,
+            target_host: String::new(),
+            target_port: 0,
+            created_at: Utc::now().to_rfc3339(),
+            stats,
+            task: Some(task),
+            connections: JoinSet::new(),
+        };
+        let info = forward.info();
+
+        self.forwards
+            .lock()
+            .await
+            .insert(forward.id.clone(), forward);
+
+        Ok(info)
+    }
+
+    /// 打开远程端口转发（ssh -R）
+    /// - 请求服务器监听 remote_address:remote_port，连接通过 forwarded-tcpip channel 转发到本地 local_host:local_port
+    pub async fn open_remote(
+        &self,
+        session_id: &str,
+        remote_address: &str,
+        remote_port: u16,
+        local_host: &str,
+        local_port: u16,
+    ) -> Result<PortForwardInfo> {
+        let transport = get_ssh_manager().get_transport(session_id).await?;
+        let allocated_port = transport
+            .write()
+            .await
+            .tcpip_forward(remote_address, remote_port as u32)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Server refused to listen on {}:{}: {}",
+                    remote_address,
+                    remote_port,
+                    e
+                )
+            })?;
+
+        // 端口为 0 时由服务器分配
+        let remote_port = if remote_port == 0 {
+            allocated_port as u16
+        } else {
+            remote_port
+        };
+
+        let forward = PortForward {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            kind: ForwardKind::Remote,
+            bind_address: remote_address.to_string(),
+            bind_port: remote_port,
+            target_host: local_host.to_string(),
+            target_port: local_port,
+            created_at: Utc::now().to_rfc3339(),
+            stats: Arc::new(ForwardStats::default()),
+            task: None,
+            connections: JoinSet::new(),
+        };
+        let info = forward.info();
+
+        self.forwards
+            .lock()
+            .await
+            .insert(forward.id.clone(), forward);
+
+        Ok(info)
+    }
+
+    /// 处理服务器打开的 forwarded-tcpip channel（由 SSHClientHandler 调用）
+    /// - 不能在这里等待 SSH 连接上的任何响应，否则会阻塞会话的事件循环
+    #[allow(clippy::too_many_arguments)]
+    pub async fn accept_remote(
+        &self,
+        session_ids: &[String],
+        channel: Channel<Msg>,
+        connected_address: &str,
+        connected_port: u16,
+        originator_address: &str,
+        originator_port: u16,
+        app_handle: Option<tauri::AppHandle>,
+    ) {
+        let mut forwards = self.forwards.lock().await;
+        let candidates: Vec<&PortForward> = forwards
+            .values()
+            .filter(|forward| {
+                forward.kind == ForwardKind::Remote
+                    && session_ids.contains(&forward.session_id)
+                    && forward.bind_port == connected_port
+            })
+            .collect();
+        // 优先匹配地址完全一致的转发，服务器可能会把地址规范化（如 localhost -> 127.0.0.1）
+        let matched = candidates
+            .iter()
+            .find(|forward| forward.bind_address == connected_address)
+            .or_else(|| candidates.first())
+            .map(|forward| forward.id.clone());
+
+        let Some(forward) = matched.and_then(|id| forwards.get_mut(&id)) else {
+            log::warn!(
+                "Unexpected forwarded-tcpip channel for {}:{}",
+                connected_address,
+                connected_port
+            );
+            tokio::spawn(async move {
+                let _ = channel.close().await;
+            });
+            return;
+        };
+
+        let forward_id = forward.id.clone();
+        let local_host = forward.target_host.clone();
+        let local_port = forward.target_port;
+        let stats = forward.stats.clone();
+        let originator = format!("{}:{}", originator_address, originator_port);
+
+        // 回收已结束的隧道任务
+        while forward.connections.try_join_next().is_some() {}
+        forward.connections.spawn(async move {
+            let event = format!("ssh-forward-connection-{}", forward_id);
+            if let Some(app_handle) = &app_handle {
+                let _ = app_handle.emit_all(
+                    &event,
+                    json!({ "forward_id": forward_id, "originator": originator, "status": "opened" }),
+                );
+            }
+
+            match TcpStream::connect((local_host.as_str(), local_port)).await {
+                Ok(socket) => tunnel(socket, channel.into_stream(), &stats).await,
+                Err(e) => {
+                    log::warn!(
+                        "Remote forward to {}:{} failed: {}",
+                        local_host,
+                        local_port,
+                        e
+                    );
+                    let _ = channel.close().await;
+                }
+            }
+
+            if let Some(app_handle) = &app_handle {
+                let _ = app_handle.emit_all(
+                    &event,
+                    json!({ "forward_id": forward_id, "originator": originator, "status": "closed" }),
+                );
+            }
+        });
+    }
+
+    /// 列出端口转发（可按会话过滤）
+    pub async fn list(&self, session_id: Option<&str>) -> Vec<PortForwardInfo> {
+        self.forwards
+            .lock()
+            .await
+            .values()
+            .filter(|forward| session_id.map_or(true, |id| forward.session_id == id))
+            .map(PortForward::info)
+            .collect()
+    }
+
+    /// 关闭端口转发
+    pub async fn close(&self, forward_id: &str) -> Result<()> {
+        let forward = self
+            .forwards
+            .lock()
+            .await
+            .remove(forward_id)
+            .ok_or_else(|| anyhow!("Port forward not found: {}", forward_id))?;
+
+        match forward.kind {
+            ForwardKind::Local | ForwardKind::Dynamic => {
+                if let Some(task) = forward.task {
+                    task.abort();
+                }
+            }
+            ForwardKind::Remote => {
+                let transport = get_ssh_manager().get_transport(&forward.session_id).await?;
+                let handle = transport.read().await;
+                handle
+                    .cancel_tcpip_forward(forward.bind_address.as_str(), forward.bind_port as u32)
+                    .await
+                    .map_err(|e| anyhow!("Failed to cancel remote port forward: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 在重连后的新连接上重新请求会话的远程端口转发
+    /// - 本地和动态转发按连接打开 channel，无需恢复
+    pub async fn restore_remote_forwards(&self, session_id: &str) {
+        let remotes: Vec<(String, u16)> = self
+            .forwards
+            .lock()
+            .await
+            .values()
+            .filter(|forward| {
+                forward.kind == ForwardKind::Remote && forward.session_id == session_id
+            })
+            .map(|forward| (forward.bind_address.clone(), forward.bind_port))
+            .collect();
+        if remotes.is_empty() {
+            return;
+        }
+
+        let transport = match get_ssh_manager().get_transport(session_id).await {
+            Ok(transport) => transport,
+            Err(e) => {
+                log::warn!("Failed to restore remote port forwards: {}", e);
+                return;
+            }
+        };
+
+        for (address, port) in remotes {
+            if let Err(e) = transport
+                .write()
+                .await
+                .tcpip_forward(address.as_str(), port as u32)
+                .await
+            {
+                log::warn!(
+                    "Failed to restore remote port forward {}:{}: {}",
+                    address,
+                    port,
+                    e
+                );
+            }
+        }
+    }
+
+    /// 关闭会话的所有端口转发（会话断开时调用）
+    /// - 远程转发需要通知服务器停止监听，共享的池化连接在会话关闭后仍然存在
+    pub async fn close_session_forwards(&self, session_id: &str) {
+        let closed: Vec<PortForward> = {
+            let mut forwards = self.forwards.lock().await;
+            let ids: Vec<String> = forwards
+                .values()
+                .filter(|forward| forward.session_id == session_id)
+                .map(|forward| forward.id.clone())
+                .collect();
+            ids.iter().filter_map(|id| forwards.remove(id)).collect()
+        };
+
+        let mut remotes = Vec::new();
+        for forward in closed {
+            if let Some(task) = &forward.task {
+                task.abort();
+            }
+            if forward.kind == ForwardKind::Remote {
+                remotes.push((forward.bind_address, forward.bind_port));
+            }
+        }
+        if remotes.is_empty() {
+            return;
+        }
+
+        let transport = match get_ssh_manager().get_transport(session_id).await {
+            Ok(transport) => transport,
+            Err(e) => {
+                log::warn!("Failed to cancel remote port forwards: {}", e);
+                return;
+            }
+        };
+
+        for (address, port) in remotes {
+            if let Err(e) = transport
+                .read()
+                .await
+                .cancel_tcpip_forward(address.as_str(), port as u32)
+                .await
+            {
+                log::warn!(
+                    "Failed to cancel remote port forward {}:{}: {}",
+                    address,
+                    port,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// 接受本地连接并逐个建立隧道
+/// - open 为每个连接打开远端流（实际使用时为会话的 direct-tcpip channel）
+async fn accept_local<F, Fut, S>(listener: TcpListener, stats: Arc<ForwardStats>, open: F)
+where
+    F: Fn(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<S>> + Send + 'static,
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    let mut connections = JoinSet::new();
+    loop {
+        let (socket, peer) = next_connection(&listener, &mut connections, "Port forward").await;
+
+        let opening = open(peer);
+        let stats = stats.clone();
+
+        connections.spawn(async move {
+            let remote = match opening.await {
+                Ok(remote) => remote,
+                Err(e) => {
+                    log::warn!("Port forward from {} failed: {}", peer, e);
+                    return;
+                }
+            };
+
+            tunnel(socket, remote, &stats).await;
+        });
+    }
+}
+
+/// 接受 SOCKS5 客户端连接，按请求的目标地址建立隧道
+async fn accept_dynamic(listener: TcpListener, session_id: String, stats: Arc<ForwardStats>) {
+    let mut connections = JoinSet::new();
+    loop {
+        let (mut socket, peer) = next_connection(&listener, &mut connections, "SOCKS5 proxy").await;
+
+        let session_id = session_id.clone();
+        let stats = stats.clone();
+
+        connections.spawn(async move {
+            let target = match socks::handshake(&mut socket).await {
+                Ok(target) => target,
+                Err(e) => {
+                    log::warn!("SOCKS5 handshake from {} failed: {}", peer, e);
+                    return;
+                }
+            };
+
+            let channel = match get_ssh_manager()
+                .open_direct_tcpip(
+                    &session_id,
+                    &target.host(),
+                    target.port(),
+                    &peer.ip().to_string(),
+                    peer.port(),
+                )
+                .await
+            {
+                Ok(channel) => channel,
+                Err(e) => {
+                    log::warn!("SOCKS5 connect from {} failed: {}", peer, e);
+                    let _ = socks::send_reply(&mut socket, socks::REPLY_CONNECTION_REFUSED).await;
+                    return;
+                }
+            };
+
+            if socks::send_reply(&mut socket, socks::REPLY_SUCCEEDED)
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            tunnel(socket, channel.into_stream(), &stats).await;
+        });
+    }
+}
+
+/// 接受下一个连接，等待期间回收已结束的隧道任务
+/// - accept 失败时逐步退避，避免持续性错误（如文件描述符耗尽）导致空转
+async fn next_connection(
+    listener: &TcpListener,
+    connections: &mut JoinSet<()>,
+    label: &str,
+) -> (TcpStream, SocketAddr) {
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => return accepted,
+                Err(e) => {
+                    log::warn!("{} accept failed: {}", label, e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                }
+            },
+            Some(_) = connections.join_next() => {}
+        }
+    }
+}
+
+/// 在本地流与 SSH channel 之间双向转发数据，并累计流量统计
+pub(crate) async fn tunnel<L, R>(local: L, remote: R, stats: &ForwardStats)
+where
+    L: AsyncRead + AsyncWrite + Unpin,
+    R: AsyncRead + AsyncWrite + Unpin,
+{
+    stats.active_connections.fetch_add(1, Ordering::Relaxed);
+    stats.total_connections.fetch_add(1, Ordering::Relaxed);
+
+    let (local_reader, local_writer) = tokio::io::split(local);
+    let (remote_reader, remote_writer) = tokio::io::split(remote);
+
+    let _ = tokio::join!(
+        pipe(local_reader, remote_writer, &stats.bytes_sent),
+        pipe(remote_reader, local_writer, &stats.bytes_received),
+    );
+
+    stats.active_connections.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// 单向转发，读到 EOF 后关闭写端
+async fn pipe<R, W>(mut reader: R, mut writer: W, counter: &AtomicU64) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0u8; 32 * 1024];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            writer.shutdown().await?;
+            return Ok(());
+        }
+        writer.write_all(&buf[..n]).await?;
+        counter.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// 全局端口转发管理器
+static FORWARD_MANAGER: Lazy<PortForwardManager> = Lazy::new(PortForwardManager::new);
+
+pub fn get_forward_manager() -> &'static PortForwardManager {
+    &FORWARD_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tunnel_counts_bytes_both_ways() {
+        let (mut client, local) = tokio::io::duplex(1024);
+        let (remote, mut server) = tokio::io::duplex(1024);
+        let stats = Arc::new(ForwardStats::default());
+
+        let tunnel_stats = stats.clone();
+        let task = tokio::spawn(async move { tunnel(local, remote, &tunnel_stats).await });
+
+        client.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        server.write_all(b"pong!").await.unwrap();
+        let mut buf = [0u8; 5];
+        client.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"pong!");
+
+        drop(client);
+        drop(server);
+        task.await.unwrap();
+
+        assert_eq!(stats.bytes_sent.load(Ordering::Relaxed), 4);
+        assert_eq!(stats.bytes_received.load(Ordering::Relaxed), 5);
+        assert_eq!(stats.active_connections.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.total_connections.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_aborting_accept_loop_closes_tunnels() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // 远端流的另一端由测试持有，隧道不会自行结束
+        let (remotes_tx, mut remotes_rx) = tokio::sync::mpsc::unbounded_channel();
+        let accept = tokio::spawn(accept_local(
+            listener,
+            Arc::new(ForwardStats::default()),
+            move |_peer| {
+                let (remote, server) = tokio::io::duplex(1024);
+                let _ = remotes_tx.send(server);
+                async move { Ok(remote) }
+            },
+        ));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"ping").await.unwrap();
+        let mut server = remotes_rx.recv().await.unwrap();
+        let mut buf = [0u8; 4];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        accept.abort();
+        let n = tokio::time::timeout(Duration::from_secs(5), client.read(&mut buf))
+            .await
+            .expect("tunnel was not closed")
+            .unwrap_or(0);
+        assert_eq!(n, 0);
+        drop(server);
+    }
+}
@@ -0,0 +1,154 @@
+use super::client::{authenticate, SSHClientHandler};
+use crate::models::connection::JumpHostConfig;
+use anyhow::{anyhow, Result};
+use russh::client::{self, Config, Handle, Msg};
+use russh::{ChannelStream, Disconnect};
+use std::sync::Arc;
+
+/// 跳板机链
+/// - 按连接顺序保存每一跳的 Handle，Handle 被释放时对应的隧道也会关闭
+pub struct JumpChain {
+    handles: Vec<Handle<SSHClientHandler>>,
+}
+
+impl JumpChain {
+    /// 依次连接并认证每一跳跳板机，最后打开到目标主机的 direct-tcpip 隧道
+    pub async fn connect(
+        config: Arc<Config>,
+        jump_host: &JumpHostConfig,
+        target_host: &str,
+        target_port: u16,
+        session_id: &str,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Result<(Self, ChannelStream<Msg>)> {
+        let hops = flatten(jump_host);
+        let total = hops.len();
+        let mut handles: Vec<Handle<SSHClientHandler>> = Vec::with_capacity(total);
+
+        for (index, hop) in hops.iter().enumerate() {
+            let label = hop_label(index, total, hop);
+            let handler =
+                SSHClientHandler::new(session_id, &hop.host, hop.port, app_handle.clone());
+
+            let mut handle = match handles.last() {
+                None => client::connect(config.clone(), (hop.host.as_str(), hop.port), handler)
+                    .await
+                    .map_err(|e| anyhow!("{}: failed to connect: {}", label, e))?,
+                Some(previous) => {
+                    let channel = previous
+                        .channel_open_direct_tcpip(
+                            hop.host.as_str(),
+                            hop.port as u32,
+                            "127.0.0.1",
+                            0,
+                        )
+                        .await
+                        .map_err(|e| anyhow!("{}: failed to open tunnel: {}", label, e))?;
+                    client::connect_stream(config.clone(), channel.into_stream(), handler)
+                        .await
+                        .map_err(|e| anyhow!("{}: failed to connect: {}", label, e))?
+                }
+            };
+
+            authenticate(
+                &mut handle,
+                &hop.username,
+                hop.auth_method.as_str(),
+                hop.password.as_deref(),
+                hop.private_key_path.as_deref(),
+                hop.passphrase.as_deref(),
+                hop.certificate_path.as_deref(),
+                session_id,
+                app_handle.as_ref(),
+            )
+            .await
+            .map_err(|e| anyhow!("{}: {}", label, e))?;
+
+            handles.push(handle);
+        }
+
+        let last = handles
+            .last()
+            .ok_or_else(|| anyhow!("Jump host chain is empty"))?;
+        let channel = last
+            .channel_open_direct_tcpip(target_host, target_port as u32, "127.0.0.1", 0)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "{}: failed to open tunnel to {}:{}: {}",
+                    hop_label(total - 1, total, hops[total - 1]),
+                    target_host,
+                    target_port,
+                    e
+                )
+            })?;
+
+        Ok((Self { handles }, channel.into_stream()))
+    }
+
+    /// 从最靠近目标的一跳开始依次断开
+    pub async fn close(&self) {
+        for handle in self.handles.iter().rev() {
+            let _ = handle
+                .disconnect(Disconnect::ByApplication, "", "English")
+                .await;
+        }
+    }
+}
+
+/// 将嵌套的跳板机配置展开为连接顺序（最外层的跳板机在前）
+fn flatten(jump_host: &JumpHostConfig) -> Vec<&JumpHostConfig> {
+    let mut hops = vec![jump_host];
+    let mut current = jump_host;
+    while let Some(previous) = current.jump_host.as_deref() {
+        hops.push(previous);
+        current = previous;
+    }
+    hops.reverse();
+    hops
+}
+
+fn hop_label(index: usize, total: usize, hop: &JumpHostConfig) -> String {
+    format!(
+        "Jump host {}/{} ({}@{}:{})",
+        index + 1,
+        total,
+        hop.username,
+        hop.host,
+        hop.port
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::connection::AuthMethod;
+
+    fn hop(host: &str, previous: Option<JumpHostConfig>) -> JumpHostConfig {
+        JumpHostConfig {
+            host: host.to_string(),
+            port: 22,
+            username: "ops".to_string(),
+            auth_method: AuthMethod::Key,
+            password: None,
+            private_key_path: None,
+            passphrase: None,
+            certificate_path: None,
+            jump_host: previous.map(Box::new),
+        }
+    }
+
+    #[test]
+    fn test_flatten_single_hop() {
+        let jump = hop("bastion", None);
+        let hosts: Vec<&str> = flatten(&jump).iter().map(|h| h.host.as_str()).collect();
+        assert_eq!(hosts, vec!["bastion"]);
+    }
+
+    #[test]
+    fn test_flatten_orders_outermost_first() {
+        let jump = hop("inner", Some(hop("middle", Some(hop("outer", None)))));
+        let hosts: Vec<&str> = flatten(&jump).iter().map(|h| h.host.as_str()).collect();
+        assert_eq!(hosts, vec!["outer", "middle", "inner"]);
+    }
+}
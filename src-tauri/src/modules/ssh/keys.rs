@@ -0,0 +1,434 @@
+use super::client::get_ssh_manager;
+use super::exec::run_command;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ssh_key::private::RsaKeypair;
+use ssh_key::rand_core::OsRng;
+use ssh_key::{Algorithm, EcdsaCurve, HashAlg, LineEnding, PrivateKey, PublicKey};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// ssh-copy-id 远程命令的超时时间
+const COPY_ID_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 密钥算法
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyAlgorithm {
+    Ed25519,
+    Rsa,
+    Ecdsa,
+}
+
+/// 密钥信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyInfo {
+    pub name: String,
+    pub key_type: String,
+    pub fingerprint: String,
+    pub comment: String,
+    /// OpenSSH 格式的公钥（authorized_keys 中的一行）
+    pub public_key: String,
+    pub private_key_path: Option<String>,
+    pub public_key_path: Option<String>,
+    pub encrypted: bool,
+    pub created_at: Option<String>,
+}
+
+impl KeyInfo {
+    fn from_public_key(name: &str, public_key: &PublicKey) -> Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            key_type: public_key.algorithm().to_string(),
+            fingerprint: public_key.fingerprint(HashAlg::Sha256).to_string(),
+            comment: public_key.comment().to_string(),
+            public_key: public_key.to_openssh()?,
+            private_key_path: None,
+            public_key_path: None,
+            encrypted: false,
+            created_at: None,
+        })
+    }
+}
+
+/// 部署公钥的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyIdResult {
+    /// false 表示公钥已存在于 authorized_keys 中
+    pub added: bool,
+    pub fingerprint: String,
+}
+
+/// 密钥存储目录（应用数据目录下的 keys）
+pub fn keys_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    let app_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| anyhow!("Failed to get app data dir"))?;
+    Ok(app_dir.join("keys"))
+}
+
+/// 生成密钥对并保存到密钥存储目录
+/// - bits: RSA 位数（默认 4096）或 ECDSA 曲线位数（256/384，默认 256），ed25519 忽略
+pub fn generate(
+    dir: &Path,
+    name: &str,
+    algorithm: KeyAlgorithm,
+    bits: Option<u32>,
+    comment: Option<&str>,
+    passphrase: Option<&str>,
+) -> Result<KeyInfo> {
+    validate_name(name)?;
+    let private_path = dir.join(name);
+    let public_path = dir.join(format!("{}.pub", name));
+    if private_path.exists() || public_path.exists() {
+        return Err(anyhow!("Key already exists: {}", name));
+    }
+
+    let mut key = match algorithm {
+        KeyAlgorithm::Ed25519 => PrivateKey::random(&mut OsRng, Algorithm::Ed25519)?,
+        KeyAlgorithm::Rsa => {
+            let bits = bits.unwrap_or(4096);
+            if !matches!(bits, 2048 | 3072 | 4096) {
+                return Err(anyhow!("Unsupported RSA key size: {}", bits));
+            }
+            let keypair = RsaKeypair::random(&mut OsRng, bits as usize)?;
+            PrivateKey::new(keypair.into(), "")?
+        }
+        KeyAlgorithm::Ecdsa => {
+            let curve = match bits.unwrap_or(256) {
+                256 => EcdsaCurve::NistP256,
+                384 => EcdsaCurve::NistP384,
+                other => return Err(anyhow!("Unsupported ECDSA curve size: {}", other)),
+            };
+            PrivateKey::random(&mut OsRng, Algorithm::Ecdsa { curve })?
+        }
+    };
+    key.set_comment(comment.unwrap_or(name));
+
+    std::fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create key store: {}", e))?;
+    write_private_key(&private_path, &key, passphrase)?;
+    std::fs::write(
+        &public_path,
+        format!("{}\n", key.public_key().to_openssh()?),
+    )
+    .map_err(|e| anyhow!("Failed to write public key: {}", e))?;
+
+    read_key_info(&private_path)
+}
+
+/// 列出密钥存储目录中的密钥（以 .pub 文件为准）
+pub fn list(dir: &Path) -> Result<Vec<KeyInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(dir).map_err(|e| anyhow!("Failed to read key store: {}", e))?;
+
+    let mut keys = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("pub") {
+            continue;
+        }
+
+        match read_key_info(&path.with_extension("")) {
+            Ok(info) => keys.push(info),
+            Err(e) => log::warn!("Skipping invalid key {}: {}", path.display(), e),
+        }
+    }
+
+    keys.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(keys)
+}
+
+/// 读取密钥信息
+/// - path 可以是私钥或公钥（.pub）路径，优先读取公钥，不需要口令
+pub fn read_key_info(path: &Path) -> Result<KeyInfo> {
+    let (private_path, public_path) = if path.extension().and_then(|e| e.to_str()) == Some("pub") {
+        (path.with_extension(""), path.to_path_buf())
+    } else {
+        (
+            path.to_path_buf(),
+            PathBuf::from(format!("{}.pub", path.display())),
+        )
+    };
+    let name = private_path
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let private_key = if private_path.is_file() {
+        Some(
+            PrivateKey::read_openssh_file(&private_path)
+                .map_err(|e| anyhow!("Failed to read private key: {}", e))?,
+        )
+    } else {
+        None
+    };
+
+    let public_key = if public_path.is_file() {
+        PublicKey::read_openssh_file(&public_path)
+            .map_err(|e| anyhow!("Failed to read public key: {}", e))?
+    } else {
+        private_key
+            .as_ref()
+            .map(|key| key.public_key().clone())
+            .ok_or_else(|| anyhow!("Key not found: {}", path.display()))?
+    };
+
+    let mut info = KeyInfo::from_public_key(&name, &public_key)?;
+    info.public_key_path = public_path
+        .is_file()
+        .then(|| public_path.to_string_lossy().into_owned());
+    if let Some(private_key) = &private_key {
+        info.private_key_path = Some(private_path.to_string_lossy().into_owned());
+        info.encrypted = private_key.is_encrypted();
+    }
+    info.created_at = std::fs::metadata(&public_path)
+        .or_else(|_| std::fs::metadata(&private_path))
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|t| DateTime::<Utc>::from(t).to_rfc3339());
+
+    Ok(info)
+}
+
+/// 修改私钥口令（new_passphrase 为空表示移除口令）
+pub fn change_passphrase(
+    path: &Path,
+    old_passphrase: Option<&str>,
+    new_passphrase: Option<&str>,
+) -> Result<KeyInfo> {
+    let key = PrivateKey::read_openssh_file(path)
+        .map_err(|e| anyhow!("Failed to read private key: {}", e))?;
+
+    let key = if key.is_encrypted() {
+        let old = old_passphrase.ok_or_else(|| anyhow!("Current passphrase is required"))?;
+        key.decrypt(old)
+            .map_err(|_| anyhow!("Current passphrase is incorrect"))?
+    } else {
+        key
+    };
+
+    write_private_key(path, &key, new_passphrase)?;
+    read_key_info(path)
+}
+
+/// 将公钥追加到远程 ~/.ssh/authorized_keys（ssh-copy-id），已存在时不重复追加
+pub async fn copy_id(session_id: &str, public_key: &str) -> Result<CopyIdResult> {
+    let public_key = PublicKey::from_openssh(public_key.trim())
+        .map_err(|e| anyhow!("Invalid public key: {}", e))?;
+    let command = copy_id_command(&public_key)?;
+
+    let transport = get_ssh_manager().get_transport(session_id).await?;
+    let output = run_command(&transport, &command, Some(COPY_ID_TIMEOUT), None, |_, _| {}).await?;
+
+    if output.timed_out {
+        return Err(anyhow!("Timed out updating authorized_keys"));
+    }
+    if output.exit_status != Some(0) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "Failed to update authorized_keys: {}",
+            stderr.trim()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(CopyIdResult {
+        added: stdout.trim() == "added",
+        fingerprint: public_key.fingerprint(HashAlg::Sha256).to_string(),
+    })
+}
+
+/// 生成幂等追加公钥的远程 shell 命令
+/// - 只按 "类型 + 公钥数据" 匹配，注释不同的同一公钥不会重复追加
+/// - 保证追加前文件以换行结尾
+fn copy_id_command(public_key: &PublicKey) -> Result<String> {
+    let line = public_key.to_openssh()?;
+    let key_data = line
+        .split_whitespace()
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Ok(format!(
+        "umask 077; mkdir -p ~/.ssh && touch ~/.ssh/authorized_keys && \
+         if grep -qF {key} ~/.ssh/authorized_keys; then echo present; else \
+         if [ -s ~/.ssh/authorized_keys ] && [ -n \"$(tail -c 1 ~/.ssh/authorized_keys)\" ]; then \
+         echo >> ~/.ssh/authorized_keys; fi; \
+         printf '%s\\n' {line} >> ~/.ssh/authorized_keys && echo added; fi",
+        key = shell_quote(&key_data),
+        line = shell_quote(&line),
+    ))
+}
+
+/// 单引号转义
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 写入私钥（可选口令加密），Unix 下权限为 0600
+/// - 先写入同目录下的临时文件再重命名覆盖，写入中途失败时不会损坏原有私钥
+fn write_private_key(path: &Path, key: &PrivateKey, passphrase: Option<&str>) -> Result<()> {
+    let key = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => key
+            .encrypt(&mut OsRng, passphrase)
+            .map_err(|e| anyhow!("Failed to encrypt private key: {}", e))?,
+        None => key.clone(),
+    };
+    let pem = key
+        .to_openssh(LineEnding::LF)
+        .map_err(|e| anyhow!("Failed to encode private key: {}", e))?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid private key path: {}", path.display()))?;
+    // 密钥名称不能以 . 开头，临时文件不会与密钥冲突
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        Uuid::new_v4()
+    ));
+
+    let written = write_new_private_file(&temp_path, pem.as_bytes())
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(anyhow!("Failed to write private key: {}", e));
+    }
+    Ok(())
+}
+
+/// 创建新文件并写入内容后落盘，Unix 下创建时权限即为 0600
+fn write_new_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// 密钥名称只允许字母、数字、-、_、.（不能以 . 开头）
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.ends_with(".pub")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid key name: {}", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("devhub-keys-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("id_ed25519").is_ok());
+        assert!(validate_name("deploy-key.prod").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../id_rsa").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("key.pub").is_err());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("abc"), "'abc'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_generate_list_and_change_passphrase() {
+        let dir = temp_dir("generate");
+
+        let info = generate(
+            &dir,
+            "deploy",
+            KeyAlgorithm::Ed25519,
+            None,
+            Some("ops@devhub"),
+            Some("old"),
+        )
+        .unwrap();
+        assert_eq!(info.key_type, "ssh-ed25519");
+        assert_eq!(info.comment, "ops@devhub");
+        assert!(info.encrypted);
+        assert!(info.fingerprint.starts_with("SHA256:"));
+
+        assert!(generate(&dir, "deploy", KeyAlgorithm::Ed25519, None, None, None).is_err());
+
+        let keys = list(&dir).unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].fingerprint, info.fingerprint);
+
+        let private_path = dir.join("deploy");
+        assert!(change_passphrase(&private_path, Some("wrong"), None).is_err());
+        let changed = change_passphrase(&private_path, Some("old"), None).unwrap();
+        assert!(!changed.encrypted);
+        assert_eq!(changed.fingerprint, info.fingerprint);
+
+        // 通过临时文件替换，不留下临时文件，权限仍为 0600
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&private_path)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_generate_ecdsa() {
+        let dir = temp_dir("ecdsa");
+
+        let info = generate(&dir, "ecdsa", KeyAlgorithm::Ecdsa, Some(384), None, None).unwrap();
+        assert_eq!(info.key_type, "ecdsa-sha2-nistp384");
+        assert_eq!(info.comment, "ecdsa");
+        assert!(generate(&dir, "bad", KeyAlgorithm::Ecdsa, Some(512), None, None).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_copy_id_command_matches_key_without_comment() {
+        let dir = temp_dir("copy-id");
+        let info = generate(&dir, "k", KeyAlgorithm::Ed25519, None, Some("a'b"), None).unwrap();
+        let public_key = PublicKey::from_openssh(&info.public_key).unwrap();
+
+        let command = copy_id_command(&public_key).unwrap();
+        let key_data: Vec<&str> = info.public_key.split_whitespace().take(2).collect();
+        assert!(command.contains(&format!("grep -qF '{}'", key_data.join(" "))));
+        assert!(command.contains("a'\\''b"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
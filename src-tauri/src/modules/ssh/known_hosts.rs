@@ -0,0 +1,449 @@
+use crate::modules::database::get_db;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use russh::keys::key::PublicKey;
+use russh::keys::PublicKeyBase64;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// 已信任的主机密钥记录（对应 devhub.db 中的 known_hosts 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownHost {
+    pub host: String,
+    pub port: u16,
+    pub key_type: String,
+    pub public_key: String,
+    pub fingerprint: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 主机密钥校验结果
+#[derive(Debug, Clone)]
+pub enum HostKeyVerdict {
+    /// 密钥与已信任的记录一致
+    Trusted,
+    /// 首次见到该主机，已按 TOFU 原则记录
+    FirstSeen(KnownHost),
+    /// 密钥与已信任的记录不一致（可能存在中间人攻击）
+    Changed {
+        old_fingerprint: String,
+        new_fingerprint: String,
+        key_type: String,
+        public_key: String,
+    },
+}
+
+/// ~/.ssh/known_hosts 同步选项（保存在 devhub.db 的 known_hosts_options 表）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct KnownHostsOptions {
+    /// 本地库中没有记录时，是否参考 ~/.ssh/known_hosts
+    pub read_openssh: bool,
+    /// 首次信任或手动接受密钥时，是否同时写入 ~/.ssh/known_hosts
+    pub write_openssh: bool,
+}
+
+impl Default for KnownHostsOptions {
+    fn default() -> Self {
+        Self {
+            read_openssh: true,
+            write_openssh: false,
+        }
+    }
+}
+
+/// 读取同步选项（未保存过时返回默认值）
+pub async fn get_options() -> Result<KnownHostsOptions> {
+    load_options(get_db().pool()).await
+}
+
+/// 保存同步选项
+pub async fn set_options(options: KnownHostsOptions) -> Result<()> {
+    store_options(get_db().pool(), options).await
+}
+
+async fn load_options(pool: &SqlitePool) -> Result<KnownHostsOptions> {
+    let row = sqlx::query_as::<_, (bool, bool)>(
+        "SELECT read_openssh, write_openssh FROM known_hosts_options WHERE id = 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Failed to query known hosts options: {}", e))?;
+
+    Ok(row
+        .map(|(read_openssh, write_openssh)| KnownHostsOptions {
+            read_openssh,
+            write_openssh,
+        })
+        .unwrap_or_default())
+}
+
+async fn store_options(pool: &SqlitePool, options: KnownHostsOptions) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO known_hosts_options (id, read_openssh, write_openssh, updated_at)
+        VALUES (1, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            read_openssh = excluded.read_openssh,
+            write_openssh = excluded.write_openssh,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(options.read_openssh)
+    .bind(options.write_openssh)
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Failed to save known hosts options: {}", e))?;
+
+    Ok(())
+}
+
+/// 计算 OpenSSH 风格的 SHA256 指纹
+pub fn fingerprint(key: &PublicKey) -> String {
+    format!("SHA256:{}", key.fingerprint())
+}
+
+/// 比较服务器密钥与已存储的记录
+fn compare(stored: Option<&KnownHost>, key_type: &str, public_key: &str) -> Option<bool> {
+    stored.map(|entry| entry.key_type == key_type && entry.public_key == public_key)
+}
+
+/// 校验服务器主机密钥（TOFU：首次见到的密钥会被记录）
+pub async fn verify_host_key(host: &str, port: u16, key: &PublicKey) -> Result<HostKeyVerdict> {
+    verify_host_key_in(get_db().pool(), host, port, key).await
+}
+
+async fn verify_host_key_in(
+    pool: &SqlitePool,
+    host: &str,
+    port: u16,
+    key: &PublicKey,
+) -> Result<HostKeyVerdict> {
+    let key_type = key.name().to_string();
+    let public_key = key.public_key_base64();
+    let new_fingerprint = fingerprint(key);
+
+    let stored = find_in(pool, host, port).await?;
+    match compare(stored.as_ref(), &key_type, &public_key) {
+        Some(true) => return Ok(HostKeyVerdict::Trusted),
+        Some(false) => {
+            let old_fingerprint = stored.map(|entry| entry.fingerprint).unwrap_or_default();
+            return Ok(HostKeyVerdict::Changed {
+                old_fingerprint,
+                new_fingerprint,
+                key_type,
+                public_key,
+            });
+        }
+        None => {}
+    }
+
+    // 本地库中没有记录时参考 ~/.ssh/known_hosts
+    let options = load_options(pool).await?;
+    if options.read_openssh {
+        match russh::keys::check_known_hosts(host, port, key) {
+            Ok(true) => {
+                save(pool, host, port, &key_type, &public_key, &new_fingerprint).await?;
+                return Ok(HostKeyVerdict::Trusted);
+            }
+            Ok(false) => {}
+            Err(russh::keys::Error::KeyChanged { .. }) => {
+                // 同一主机可能记录了多种类型的密钥，只与同类型的比较
+                let old_fingerprint = russh::keys::known_host_keys(host, port)
+                    .ok()
+                    .and_then(|keys| keys.into_iter().find(|(_, old)| old.name() == key.name()))
+                    .map(|(_, old)| fingerprint(&old))
+                    .unwrap_or_else(|| "unknown".to_string());
+                return Ok(HostKeyVerdict::Changed {
+                    old_fingerprint,
+                    new_fingerprint,
+                    key_type,
+                    public_key,
+                });
+            }
+            Err(e) => log::warn!("Failed to read ~/.ssh/known_hosts: {}", e),
+        }
+    }
+
+    let entry = save(pool, host, port, &key_type, &public_key, &new_fingerprint).await?;
+    if options.write_openssh {
+        learn_openssh(host, port, key);
+    }
+
+    Ok(HostKeyVerdict::FirstSeen(entry))
+}
+
+/// 查找主机的已信任密钥
+pub async fn find(host: &str, port: u16) -> Result<Option<KnownHost>> {
+    find_in(get_db().pool(), host, port).await
+}
+
+async fn find_in(pool: &SqlitePool, host: &str, port: u16) -> Result<Option<KnownHost>> {
+    let row = sqlx::query_as::<_, (String, i64, String, String, String, String, String)>(
+        "SELECT host, port, key_type, public_key, fingerprint, created_at, updated_at FROM known_hosts WHERE host = ? AND port = ?",
+    )
+    .bind(host)
+    .bind(port as i64)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Failed to query known host: {}", e))?;
+
+    Ok(row.map(into_known_host))
+}
+
+/// 列出所有已信任的主机密钥
+pub async fn list() -> Result<Vec<KnownHost>> {
+    let db = get_db();
+
+    let rows = sqlx::query_as::<_, (String, i64, String, String, String, String, String)>(
+        "SELECT host, port, key_type, public_key, fingerprint, created_at, updated_at FROM known_hosts ORDER BY host, port",
+    )
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| anyhow!("Failed to list known hosts: {}", e))?;
+
+    Ok(rows.into_iter().map(into_known_host).collect())
+}
+
+/// 手动接受（或替换）主机密钥
+pub async fn accept(host: &str, port: u16, key_type: &str, public_key: &str) -> Result<KnownHost> {
+    let key = russh::keys::parse_public_key_base64(public_key)
+        .map_err(|e| anyhow!("Invalid public key: {}", e))?;
+    if key.name() != key_type {
+        return Err(anyhow!(
+            "Key type mismatch: expected {}, got {}",
+            key_type,
+            key.name()
+        ));
+    }
+
+    let pool = get_db().pool();
+    let entry = save(pool, host, port, key_type, public_key, &fingerprint(&key)).await?;
+    if load_options(pool).await?.write_openssh {
+        learn_openssh(host, port, &key);
+    }
+
+    Ok(entry)
+}
+
+/// 撤销主机的已信任密钥
+pub async fn revoke(host: &str, port: u16) -> Result<bool> {
+    let db = get_db();
+
+    let result = sqlx::query("DELETE FROM known_hosts WHERE host = ? AND port = ?")
+        .bind(host)
+        .bind(port as i64)
+        .execute(db.pool())
+        .await
+        .map_err(|e| anyhow!("Failed to revoke known host: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+async fn save(
+    pool: &SqlitePool,
+    host: &str,
+    port: u16,
+    key_type: &str,
+    public_key: &str,
+    fingerprint: &str,
+) -> Result<KnownHost> {
+    let now = Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO known_hosts (host, port, key_type, public_key, fingerprint, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(host, port) DO UPDATE SET
+            key_type = excluded.key_type,
+            public_key = excluded.public_key,
+            fingerprint = excluded.fingerprint,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(host)
+    .bind(port as i64)
+    .bind(key_type)
+    .bind(public_key)
+    .bind(fingerprint)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Failed to save known host: {}", e))?;
+
+    find_in(pool, host, port)
+        .await?
+        .ok_or_else(|| anyhow!("Known host not found after save: {}:{}", host, port))
+}
+
+fn learn_openssh(host: &str, port: u16, key: &PublicKey) {
+    if let Err(e) = russh::keys::learn_known_hosts(host, port, key) {
+        log::warn!("Failed to write ~/.ssh/known_hosts: {}", e);
+    }
+}
+
+fn into_known_host(
+    (host, port, key_type, public_key, fingerprint, created_at, updated_at): (
+        String,
+        i64,
+        String,
+        String,
+        String,
+        String,
+        String,
+    ),
+) -> KnownHost {
+    KnownHost {
+        host,
+        port: port as u16,
+        key_type,
+        public_key,
+        fingerprint,
+        created_at,
+        updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::database::Database;
+    use russh::keys::key::KeyPair;
+
+    const HOST: &str = "example.com";
+    const PORT: u16 = 22;
+
+    /// 内存数据库，关闭 ~/.ssh/known_hosts 读写以免依赖本机环境
+    async fn test_db() -> Database {
+        let db = Database::in_memory().await.unwrap();
+        store_options(
+            db.pool(),
+            KnownHostsOptions {
+                read_openssh: false,
+                write_openssh: false,
+            },
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    fn generate_key() -> PublicKey {
+        KeyPair::generate_ed25519()
+            .unwrap()
+            .clone_public_key()
+            .unwrap()
+    }
+
+    fn entry(key_type: &str, public_key: &str) -> KnownHost {
+        KnownHost {
+            host: "example.com".to_string(),
+            port: 22,
+            key_type: key_type.to_string(),
+            public_key: public_key.to_string(),
+            fingerprint: "SHA256:old".to_string(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_unknown_host() {
+        assert_eq!(compare(None, "ssh-ed25519", "AAAA"), None);
+    }
+
+    #[test]
+    fn test_compare_matching_key() {
+        let stored = entry("ssh-ed25519", "AAAA");
+        assert_eq!(compare(Some(&stored), "ssh-ed25519", "AAAA"), Some(true));
+    }
+
+    #[test]
+    fn test_compare_changed_key() {
+        let stored = entry("ssh-ed25519", "AAAA");
+        assert_eq!(compare(Some(&stored), "ssh-ed25519", "BBBB"), Some(false));
+        assert_eq!(compare(Some(&stored), "ssh-rsa", "AAAA"), Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_verify_first_seen_records_key() {
+        let db = test_db().await;
+        let key = generate_key();
+
+        let verdict = verify_host_key_in(db.pool(), HOST, PORT, &key)
+            .await
+            .unwrap();
+        let HostKeyVerdict::FirstSeen(entry) = verdict else {
+            panic!("expected FirstSeen, got {:?}", verdict);
+        };
+        assert_eq!(entry.key_type, "ssh-ed25519");
+        assert_eq!(entry.public_key, key.public_key_base64());
+        assert_eq!(entry.fingerprint, fingerprint(&key));
+
+        let stored = find_in(db.pool(), HOST, PORT).await.unwrap().unwrap();
+        assert_eq!(stored.public_key, entry.public_key);
+    }
+
+    #[tokio::test]
+    async fn test_verify_trusted_key() {
+        let db = test_db().await;
+        let key = generate_key();
+
+        verify_host_key_in(db.pool(), HOST, PORT, &key)
+            .await
+            .unwrap();
+        let verdict = verify_host_key_in(db.pool(), HOST, PORT, &key)
+            .await
+            .unwrap();
+        assert!(matches!(verdict, HostKeyVerdict::Trusted), "{:?}", verdict);
+    }
+
+    #[tokio::test]
+    async fn test_verify_changed_key_keeps_record() {
+        let db = test_db().await;
+        let old = generate_key();
+        let new = generate_key();
+
+        verify_host_key_in(db.pool(), HOST, PORT, &old)
+            .await
+            .unwrap();
+        let verdict = verify_host_key_in(db.pool(), HOST, PORT, &new)
+            .await
+            .unwrap();
+        let HostKeyVerdict::Changed {
+            old_fingerprint,
+            new_fingerprint,
+            public_key,
+            ..
+        } = verdict
+        else {
+            panic!("expected Changed, got {:?}", verdict);
+        };
+        assert_eq!(old_fingerprint, fingerprint(&old));
+        assert_eq!(new_fingerprint, fingerprint(&new));
+        assert_eq!(public_key, new.public_key_base64());
+
+        // 密钥变化时不能覆盖已信任的记录
+        let stored = find_in(db.pool(), HOST, PORT).await.unwrap().unwrap();
+        assert_eq!(stored.public_key, old.public_key_base64());
+    }
+
+    #[tokio::test]
+    async fn test_options_persisted() {
+        let db = Database::in_memory().await.unwrap();
+        let defaults = load_options(db.pool()).await.unwrap();
+        assert!(defaults.read_openssh);
+        assert!(!defaults.write_openssh);
+
+        let options = KnownHostsOptions {
+            read_openssh: false,
+            write_openssh: true,
+        };
+        store_options(db.pool(), options).await.unwrap();
+        let loaded = load_options(db.pool()).await.unwrap();
+        assert!(!loaded.read_openssh);
+        assert!(loaded.write_openssh);
+    }
+}
@@ -1 +1,21 @@
+pub mod agent;
+pub mod auth_prompt;
+pub mod cert;
 pub mod client;
+pub mod exec;
+pub mod forward;
+pub mod jump;
+pub mod keys;
+pub mod known_hosts;
+pub mod monitor;
+pub mod openssh_config;
+pub mod output;
+pub mod pool;
+pub mod processes;
+pub mod recorder;
+pub mod replay;
+pub mod scrollback;
+pub mod sftp;
+pub mod socks;
+pub mod transfer;
+pub mod triggers;
@@ -0,0 +1,703 @@
+use super::client::get_ssh_manager;
+use super::exec::run_command;
+use crate::modules::database::get_db;
+use anyhow::{anyhow, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::sync::{Mutex, Notify};
+
+/// 默认采集间隔
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 最小采集间隔
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 单次采集的超时时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 历史数据保留时长
+const HISTORY_RETENTION_MINUTES: i64 = 60;
+
+/// 采集命令：一次 exec 读取所有指标，各部分以 @@ 开头的行分隔
+const PROBE_COMMAND: &str = "echo @@stat; cat /proc/stat 2>/dev/null; \
+     echo @@meminfo; cat /proc/meminfo 2>/dev/null; \
+     echo @@loadavg; cat /proc/loadavg 2>/dev/null; \
+     echo @@uptime; cat /proc/uptime 2>/dev/null; \
+     echo @@netdev; cat /proc/net/dev 2>/dev/null; \
+     echo @@df; LC_ALL=C df -P -k 2>/dev/null";
+
+/// 不统计的伪文件系统
+const PSEUDO_FILESYSTEMS: &[&str] = &["tmpfs", "devtmpfs", "udev", "none", "shm", "efivarfs"];
+
+/// CPU 指标
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CpuMetrics {
+    /// 两次采集之间的 CPU 使用率（首次采集时为 None）
+    pub usage_percent: Option<f64>,
+    pub cores: usize,
+}
+
+/// 内存指标（字节）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoryMetrics {
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+}
+
+/// 系统负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// 磁盘使用情况（字节）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiskUsage {
+    pub filesystem: String,
+    pub mount_point: String,
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub usage_percent: f64,
+}
+
+/// 网卡流量
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    /// 累计接收字节数
+    pub rx_bytes: u64,
+    /// 累计发送字节数
+    pub tx_bytes: u64,
+    /// 两次采集之间的接收速率（字节/秒）
+    pub rx_rate: Option<f64>,
+    /// 两次采集之间的发送速率（字节/秒）
+    pub tx_rate: Option<f64>,
+}
+
+/// 一次采集的主机指标（ssh-metrics-{id} 事件）
+/// - 远程主机没有对应的 /proc 文件时相应字段为空
+#[derive(Debug, Clone, Serialize)]
+pub struct HostMetrics {
+    pub session_id: String,
+    pub timestamp: String,
+    pub cpu: Option<CpuMetrics>,
+    pub memory: Option<MemoryMetrics>,
+    pub load: Option<LoadAverage>,
+    pub uptime_secs: Option<u64>,
+    pub disks: Vec<DiskUsage>,
+    pub network: Vec<NetworkInterface>,
+}
+
+/// 历史记录中的一条采样（对应 devhub.db 中的 host_metrics 表）
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSample {
+    pub timestamp: String,
+    pub cpu_usage: Option<f64>,
+    pub memory_used: Option<i64>,
+    pub memory_total: Option<i64>,
+    pub load1: Option<f64>,
+    /// 所有磁盘已用空间之和
+    pub disk_used: i64,
+    pub disk_total: i64,
+    /// 所有网卡（不含 lo）的接收速率之和
+    pub rx_rate: Option<f64>,
+    pub tx_rate: Option<f64>,
+}
+
+impl From<&HostMetrics> for MetricsSample {
+    fn from(metrics: &HostMetrics) -> Self {
+        let rate_sum = |rate: fn(&NetworkInterface) -> Option<f64>| {
+            metrics
+                .network
+                .iter()
+                .filter_map(rate)
+                .fold(None, |sum, rate| Some(sum.unwrap_or(0.0) + rate))
+        };
+
+        Self {
+            timestamp: metrics.timestamp.clone(),
+            cpu_usage: metrics.cpu.as_ref().and_then(|cpu| cpu.usage_percent),
+            memory_used: metrics.memory.as_ref().map(|m| m.used as i64),
+            memory_total: metrics.memory.as_ref().map(|m| m.total as i64),
+            load1: metrics.load.as_ref().map(|load| load.one),
+            disk_used: metrics.disks.iter().map(|d| d.used as i64).sum(),
+            disk_total: metrics.disks.iter().map(|d| d.total as i64).sum(),
+            rx_rate: rate_sum(|iface| iface.rx_rate),
+            tx_rate: rate_sum(|iface| iface.tx_rate),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CpuTimes {
+    total: u64,
+    idle: u64,
+}
+
+/// 解析采集输出，CPU 使用率和网络速率根据上一次采集计算
+#[derive(Debug, Default)]
+struct Sampler {
+    cpu: Option<CpuTimes>,
+    network: HashMap<String, (u64, u64)>,
+    sampled_at: Option<Instant>,
+}
+
+impl Sampler {
+    fn sample(&mut self, session_id: &str, output: &str, now: Instant) -> HostMetrics {
+        let sections = split_sections(output);
+        let section = |name: &str| sections.get(name).copied().unwrap_or("");
+        let elapsed = self
+            .sampled_at
+            .map(|at| now.duration_since(at).as_secs_f64())
+            .filter(|secs| *secs > 0.0);
+
+        let cpu = parse_cpu(section("stat")).map(|(times, cores)| {
+            let usage_percent = self.cpu.and_then(|prev| {
+                let total = times.total.checked_sub(prev.total)?;
+                let idle = times.idle.checked_sub(prev.idle)?;
+                (total > 0).then(|| (total - idle.min(total)) as f64 * 100.0 / total as f64)
+            });
+            self.cpu = Some(times);
+            CpuMetrics {
+                usage_percent,
+                cores,
+            }
+        });
+
+        let network = parse_net_dev(section("netdev"))
+            .into_iter()
+            .map(|(name, rx_bytes, tx_bytes)| {
+                let rate = |current: u64, previous: u64| {
+                    let elapsed = elapsed?;
+                    Some(current.checked_sub(previous)? as f64 / elapsed)
+                };
+                let previous = self.network.insert(name.clone(), (rx_bytes, tx_bytes));
+                NetworkInterface {
+                    rx_rate: previous.and_then(|(rx, _)| rate(rx_bytes, rx)),
+                    tx_rate: previous.and_then(|(_, tx)| rate(tx_bytes, tx)),
+                    name,
+                    rx_bytes,
+                    tx_bytes,
+                }
+            })
+            .collect();
+        self.sampled_at = Some(now);
+
+        HostMetrics {
+            session_id: session_id.to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            cpu,
+            memory: parse_meminfo(section("meminfo")),
+            load: parse_loadavg(section("loadavg")),
+            uptime_secs: section("uptime")
+                .split_whitespace()
+                .next()
+                .and_then(|secs| secs.parse::<f64>().ok())
+                .map(|secs| secs as u64),
+            disks: parse_df(section("df")),
+            network,
+        }
+    }
+}
+
+/// 按 @@name 行拆分采集输出
+fn split_sections(output: &str) -> HashMap<&str, &str> {
+    let mut sections = HashMap::new();
+    let mut current: Option<(&str, usize)> = None;
+    let mut offset = 0;
+
+    for line in output.split_inclusive('\n') {
+        if let Some(name) = line.trim_end().strip_prefix("@@") {
+            if let Some((prev, start)) = current {
+                sections.insert(prev, &output[start..offset]);
+            }
+            current = Some((name, offset + line.len()));
+        }
+        offset += line.len();
+    }
+    if let Some((prev, start)) = current {
+        sections.insert(prev, &output[start..]);
+    }
+    sections
+}
+
+/// 解析 /proc/stat，返回总 CPU 时间和核数
+fn parse_cpu(stat: &str) -> Option<(CpuTimes, usize)> {
+    let mut times = None;
+    let mut cores = 0;
+
+    for line in stat.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("cpu") => {
+                // user nice system idle iowait irq softirq steal（guest 已计入 user）
+                let values: Vec<u64> = fields.take(8).filter_map(|v| v.parse().ok()).collect();
+                if values.len() >= 4 {
+                    times = Some(CpuTimes {
+                        total: values.iter().sum(),
+                        idle: values[3] + values.get(4).copied().unwrap_or(0),
+                    });
+                }
+            }
+            Some(name) if name.starts_with("cpu") => cores += 1,
+            _ => {}
+        }
+    }
+
+    times.map(|times| (times, cores.max(1)))
+}
+
+/// 解析 /proc/meminfo（单位 kB）
+fn parse_meminfo(meminfo: &str) -> Option<MemoryMetrics> {
+    let values: HashMap<&str, u64> = meminfo
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let kb = rest.split_whitespace().next()?.parse::<u64>().ok()?;
+            Some((key.trim(), kb * 1024))
+        })
+        .collect();
+
+    let total = *values.get("MemTotal")?;
+    // 旧内核没有 MemAvailable
+    let available = values.get("MemAvailable").copied().unwrap_or_else(|| {
+        ["MemFree", "Buffers", "Cached"]
+            .iter()
+            .filter_map(|key| values.get(key))
+            .sum()
+    });
+    let swap_total = values.get("SwapTotal").copied().unwrap_or(0);
+    let swap_free = values.get("SwapFree").copied().unwrap_or(0);
+
+    Some(MemoryMetrics {
+        total,
+        used: total.saturating_sub(available),
+        available: available.min(total),
+        swap_total,
+        swap_used: swap_total.saturating_sub(swap_free),
+    })
+}
+
+/// 解析 /proc/loadavg
+fn parse_loadavg(loadavg: &str) -> Option<LoadAverage> {
+    let mut fields = loadavg.split_whitespace().map(|v| v.parse::<f64>().ok());
+    Some(LoadAverage {
+        one: fields.next()??,
+        five: fields.next()??,
+        fifteen: fields.next()??,
+    })
+}
+
+/// 解析 /proc/net/dev，返回 (网卡, 接收字节数, 发送字节数)，不含 lo
+fn parse_net_dev(net_dev: &str) -> Vec<(String, u64, u64)> {
+    net_dev
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let name = name.trim();
+            if name == "lo" {
+                return None;
+            }
+            let values: Vec<u64> = rest
+                .split_whitespace()
+                .map(|v| v.parse().ok())
+                .collect::<Option<_>>()?;
+            Some((name.to_string(), *values.first()?, *values.get(8)?))
+        })
+        .collect()
+}
+
+/// 解析 df -P -k 的输出（单位 1024 字节），跳过伪文件系统和重复的挂载
+fn parse_df(df: &str) -> Vec<DiskUsage> {
+    let mut seen = HashSet::new();
+
+    df.lines()
+        .skip_while(|line| line.starts_with("Filesystem"))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let filesystem = fields[0];
+            if PSEUDO_FILESYSTEMS.contains(&filesystem) || filesystem.starts_with("/dev/loop") {
+                return None;
+            }
+
+            let total = fields[1].parse::<u64>().ok()? * 1024;
+            let used = fields[2].parse::<u64>().ok()? * 1024;
+            let available = fields[3].parse::<u64>().ok()? * 1024;
+            if total == 0 || !seen.insert(filesystem) {
+                return None;
+            }
+
+            // 与 df 相同，按 已用 / (已用 + 可用) 计算
+            let usable = used + available;
+            Some(DiskUsage {
+                filesystem: filesystem.to_string(),
+                mount_point: fields[5..].join(" "),
+                total,
+                used,
+                available,
+                usage_percent: if usable > 0 {
+                    used as f64 * 100.0 / usable as f64
+                } else {
+                    0.0
+                },
+            })
+        })
+        .collect()
+}
+
+/// 正在运行的监控任务
+struct Monitor {
+    stop: Arc<Notify>,
+    latest: Arc<std::sync::Mutex<Option<HostMetrics>>>,
+}
+
+/// 主机资源监控管理器
+/// - 每个会话一个采集任务，按间隔在会话的连接上执行采集命令
+/// - 结果通过 ssh-metrics-{id} 事件推送，失败时发送 ssh-metrics-error-{id} 事件
+/// - 汇总数据写入 devhub.db，保留最近一小时
+pub struct MonitorManager {
+    monitors: Mutex<HashMap<String, Monitor>>,
+}
+
+impl MonitorManager {
+    pub fn new() -> Self {
+        Self {
+            monitors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 开始监控会话所在的主机（已在监控时按新的间隔重新开始）
+    pub async fn start(&self, session_id: &str, interval: Duration) -> Result<()> {
+        let manager = get_ssh_manager();
+        let info = manager.session_info(session_id).await?;
+        let app_handle = manager.app_handle().await?;
+
+        let monitor = Monitor {
+            stop: Arc::new(Notify::new()),
+            latest: Arc::new(std::sync::Mutex::new(None)),
+        };
+        tokio::spawn(run_monitor(
+            session_id.to_string(),
+            info.host,
+            info.port,
+            interval.max(MIN_INTERVAL),
+            monitor.stop.clone(),
+            monitor.latest.clone(),
+            app_handle,
+        ));
+
+        let previous = self
+            .monitors
+            .lock()
+            .await
+            .insert(session_id.to_string(), monitor);
+        if let Some(previous) = previous {
+            previous.stop.notify_one();
+        }
+        Ok(())
+    }
+
+    /// 停止监控，返回是否正在监控
+    pub async fn stop(&self, session_id: &str) -> bool {
+        match self.monitors.lock().await.remove(session_id) {
+            Some(monitor) => {
+                monitor.stop.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 最近一次采集的指标
+    pub async fn latest(&self, session_id: &str) -> Option<HostMetrics> {
+        let monitors = self.monitors.lock().await;
+        let monitor = monitors.get(session_id)?;
+        let latest = monitor.latest.lock().unwrap().clone();
+        latest
+    }
+
+    /// 任务结束时移除（仅当仍是同一个任务时）
+    async fn finished(&self, session_id: &str, stop: &Arc<Notify>) {
+        let mut monitors = self.monitors.lock().await;
+        if monitors
+            .get(session_id)
+            .is_some_and(|monitor| Arc::ptr_eq(&monitor.stop, stop))
+        {
+            monitors.remove(session_id);
+        }
+    }
+}
+
+/// 监控任务，会话关闭或停止监控时退出
+async fn run_monitor(
+    session_id: String,
+    host: String,
+    port: u16,
+    interval: Duration,
+    stop: Arc<Notify>,
+    latest: Arc<std::sync::Mutex<Option<HostMetrics>>>,
+    app_handle: tauri::AppHandle,
+) {
+    let mut sampler = Sampler::default();
+
+    loop {
+        let next = tokio::time::Instant::now() + interval;
+
+        let Ok(transport) = get_ssh_manager().get_transport(&session_id).await else {
+            break;
+        };
+        let result = run_command(
+            &transport,
+            PROBE_COMMAND,
+            Some(PROBE_TIMEOUT),
+            Some(stop.clone()),
+            |_, _| {},
+        )
+        .await;
+
+        match result {
+            Ok(output) if output.cancelled => break,
+            Ok(output) if output.timed_out => {
+                emit_error(&app_handle, &session_id, "Probe timed out")
+            }
+            Ok(output) => {
+                let text = String::from_utf8_lossy(&output.stdout);
+                let metrics = sampler.sample(&session_id, &text, Instant::now());
+                let _ = app_handle.emit_all(&format!("ssh-metrics-{}", session_id), &metrics);
+
+                if let Err(e) = save_sample(&host, port, &MetricsSample::from(&metrics)).await {
+                    log::warn!("Failed to save host metrics: {}", e);
+                }
+                *latest.lock().unwrap() = Some(metrics);
+            }
+            Err(e) => emit_error(&app_handle, &session_id, &e.to_string()),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep_until(next) => {}
+            _ = stop.notified() => break,
+        }
+    }
+
+    get_monitor_manager().finished(&session_id, &stop).await;
+}
+
+fn emit_error(app_handle: &tauri::AppHandle, session_id: &str, error: &str) {
+    let _ = app_handle.emit_all(
+        &format!("ssh-metrics-error-{}", session_id),
+        json!({ "session_id": session_id, "error": error }),
+    );
+}
+
+/// 保存一条采样并清理过期的历史数据
+async fn save_sample(host: &str, port: u16, sample: &MetricsSample) -> Result<()> {
+    let db = get_db();
+    let cutoff = (Utc::now() - ChronoDuration::minutes(HISTORY_RETENTION_MINUTES)).to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO host_metrics (host, port, timestamp, cpu_usage, memory_used, memory_total, load1, disk_used, disk_total, rx_rate, tx_rate)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(host)
+    .bind(port as i64)
+    .bind(&sample.timestamp)
+    .bind(sample.cpu_usage)
+    .bind(sample.memory_used)
+    .bind(sample.memory_total)
+    .bind(sample.load1)
+    .bind(sample.disk_used)
+    .bind(sample.disk_total)
+    .bind(sample.rx_rate)
+    .bind(sample.tx_rate)
+    .execute(db.pool())
+    .await
+    .map_err(|e| anyhow!("Failed to save host metrics: {}", e))?;
+
+    sqlx::query("DELETE FROM host_metrics WHERE timestamp < ?")
+        .bind(&cutoff)
+        .execute(db.pool())
+        .await
+        .map_err(|e| anyhow!("Failed to prune host metrics: {}", e))?;
+
+    Ok(())
+}
+
+/// 查询主机最近 minutes 分钟的历史数据（按时间排序）
+pub async fn history(host: &str, port: u16, minutes: u32) -> Result<Vec<MetricsSample>> {
+    let db = get_db();
+    let since = (Utc::now() - ChronoDuration::minutes(minutes as i64)).to_rfc3339();
+
+    let rows = sqlx::query_as::<_, MetricsRow>(
+        "SELECT timestamp, cpu_usage, memory_used, memory_total, load1, disk_used, disk_total, rx_rate, tx_rate FROM host_metrics WHERE host = ? AND port = ? AND timestamp >= ? ORDER BY timestamp",
+    )
+    .bind(host)
+    .bind(port as i64)
+    .bind(&since)
+    .fetch_all(db.pool())
+    .await
+    .map_err(|e| anyhow!("Failed to query host metrics: {}", e))?;
+
+    Ok(rows.into_iter().map(into_sample).collect())
+}
+
+type MetricsRow = (
+    String,
+    Option<f64>,
+    Option<i64>,
+    Option<i64>,
+    Option<f64>,
+    i64,
+    i64,
+    Option<f64>,
+    Option<f64>,
+);
+
+fn into_sample(row: MetricsRow) -> MetricsSample {
+    let (
+        timestamp,
+        cpu_usage,
+        memory_used,
+        memory_total,
+        load1,
+        disk_used,
+        disk_total,
+        rx_rate,
+        tx_rate,
+    ) = row;
+    MetricsSample {
+        timestamp,
+        cpu_usage,
+        memory_used,
+        memory_total,
+        load1,
+        disk_used,
+        disk_total,
+        rx_rate,
+        tx_rate,
+    }
+}
+
+/// 全局监控管理器
+static MONITOR_MANAGER: Lazy<MonitorManager> = Lazy::new(MonitorManager::new);
+
+pub fn get_monitor_manager() -> &'static MonitorManager {
+    &MONITOR_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "@@stat
+cpu  100 0 50 800 50 0 0 0 0 0
+cpu0 50 0 25 400 25 0 0 0 0 0
+cpu1 50 0 25 400 25 0 0 0 0 0
+intr 12345
+@@meminfo
+MemTotal:        2048000 kB
+MemFree:          512000 kB
+MemAvailable:    1024000 kB
+SwapTotal:       1000000 kB
+SwapFree:         750000 kB
+@@loadavg
+0.52 0.38 0.21 1/234 5678
+@@uptime
+12345.67 23456.78
+@@netdev
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:    9999      10    0    0    0     0          0         0     9999      10    0    0    0     0       0          0
+  eth0: 1000000    1000    0    0    0     0          0         0   500000     800    0    0    0     0       0          0
+@@df
+Filesystem     1024-blocks    Used Available Capacity Mounted on
+/dev/sda1         10000000 6000000   3000000      67% /
+tmpfs               100000       0    100000       0% /dev/shm
+/dev/sdb1         20000000 1000000  19000000       5% /mnt/my data
+/dev/sda1         10000000 6000000   3000000      67% /var/lib/docker
+";
+
+    #[test]
+    fn test_parse_sample() {
+        let mut sampler = Sampler::default();
+        let metrics = sampler.sample("s1", SAMPLE, Instant::now());
+
+        let cpu = metrics.cpu.unwrap();
+        assert_eq!(cpu.cores, 2);
+        assert_eq!(cpu.usage_percent, None);
+
+        let memory = metrics.memory.unwrap();
+        assert_eq!(memory.total, 2048000 * 1024);
+        assert_eq!(memory.used, 1024000 * 1024);
+        assert_eq!(memory.swap_used, 250000 * 1024);
+
+        assert_eq!(metrics.load.unwrap().five, 0.38);
+        assert_eq!(metrics.uptime_secs, Some(12345));
+
+        assert_eq!(metrics.network.len(), 1);
+        assert_eq!(metrics.network[0].name, "eth0");
+        assert_eq!(metrics.network[0].rx_bytes, 1000000);
+        assert_eq!(metrics.network[0].tx_bytes, 500000);
+        assert_eq!(metrics.network[0].rx_rate, None);
+
+        let mounts: Vec<&str> = metrics
+            .disks
+            .iter()
+            .map(|d| d.mount_point.as_str())
+            .collect();
+        assert_eq!(mounts, ["/", "/mnt/my data"]);
+        assert_eq!(metrics.disks[0].total, 10000000 * 1024);
+        assert!((metrics.disks[0].usage_percent - 66.666).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_rates_between_samples() {
+        let mut sampler = Sampler::default();
+        let start = Instant::now();
+        sampler.sample("s1", SAMPLE, start);
+
+        let next = SAMPLE
+            .replace(
+                "cpu  100 0 50 800 50 0 0 0 0 0",
+                "cpu  150 0 100 850 50 0 0 0 0 0",
+            )
+            .replace("eth0: 1000000", "eth0: 1200000");
+        let metrics = sampler.sample("s1", &next, start + Duration::from_secs(2));
+
+        // 150 个时间片中 idle 占 50
+        let usage = metrics.cpu.as_ref().unwrap().usage_percent.unwrap();
+        assert!((usage - 66.666).abs() < 0.01);
+        assert_eq!(metrics.network[0].rx_rate, Some(100000.0));
+        assert_eq!(metrics.network[0].tx_rate, Some(0.0));
+
+        let sample = MetricsSample::from(&metrics);
+        assert_eq!(sample.rx_rate, Some(100000.0));
+        assert_eq!(sample.disk_total, 30000000 * 1024);
+    }
+
+    #[test]
+    fn test_missing_proc() {
+        let mut sampler = Sampler::default();
+        let metrics = sampler.sample("s1", "@@stat\n@@meminfo\n@@df\n", Instant::now());
+        assert!(metrics.cpu.is_none());
+        assert!(metrics.memory.is_none());
+        assert!(metrics.load.is_none());
+        assert!(metrics.disks.is_empty());
+    }
+}
@@ -0,0 +1,1224 @@
+use crate::models::connection::{AuthMethod, JumpHostConfig, SSHConfig};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Include 最大嵌套深度（与 OpenSSH 的 READCONF_MAX_DEPTH 一致）
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// ProxyJump 递归解析的最大深度
+const MAX_JUMP_DEPTH: usize = 8;
+
+/// 能映射到连接配置的选项，其余选项导入时忽略
+const SUPPORTED_OPTIONS: &[&str] = &[
+    "host",
+    "hostname",
+    "port",
+    "user",
+    "identityfile",
+    "certificatefile",
+    "proxyjump",
+];
+
+/// 从 ssh config 中解析出的主机
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedHost {
+    /// Host 别名，用作连接名称
+    pub name: String,
+    pub config: SSHConfig,
+    /// 未能映射而被忽略的选项（如 "ForwardAgent yes"）
+    pub ignored_options: Vec<String>,
+}
+
+/// 未能映射的条目
+#[derive(Debug, Clone, Serialize)]
+pub struct UnmappedEntry {
+    pub host: String,
+    pub reason: String,
+}
+
+/// ssh config 解析结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub hosts: Vec<ImportedHost>,
+    pub unmapped: Vec<UnmappedEntry>,
+}
+
+/// 配置块：第一个 Host 之前的全局选项、Host 块或（不支持的）Match 块
+#[derive(Debug)]
+struct Block {
+    /// None 表示 Match 块，解析时整体跳过
+    patterns: Option<Vec<String>>,
+    /// Include 所在的外层块（下标），外层块匹配时本块才生效（与 OpenSSH 相同，Include 受所在块约束）
+    parent: Option<usize>,
+    /// Include 展开前后延续外层块的块，不对应配置中的 Host 行
+    continuation: bool,
+    options: Vec<(String, Vec<String>)>,
+}
+
+impl Block {
+    fn new(patterns: Option<Vec<String>>, parent: Option<usize>) -> Self {
+        Self {
+            patterns,
+            parent,
+            continuation: false,
+            options: Vec::new(),
+        }
+    }
+
+    /// 延续外层块 enclosing 的块：匹配条件与外层块相同
+    fn continuation(enclosing: usize) -> Self {
+        Self {
+            patterns: Some(vec!["*".to_string()]),
+            parent: Some(enclosing),
+            continuation: true,
+            options: Vec::new(),
+        }
+    }
+}
+
+/// 解析后的 ssh config
+#[derive(Debug)]
+pub struct OpenSSHConfig {
+    blocks: Vec<Block>,
+    home: PathBuf,
+    unmapped: Vec<UnmappedEntry>,
+}
+
+/// 解析 ssh config 文件，path 为空时读取 ~/.ssh/config
+pub fn import(path: Option<&str>) -> Result<ImportReport> {
+    let home = home_dir().ok_or_else(|| anyhow!("Failed to determine home directory"))?;
+    let path = match path {
+        Some(path) => expand_tilde(path, &home),
+        None => home.join(".ssh").join("config"),
+    };
+    let config = OpenSSHConfig::load(&path, &home)?;
+    Ok(config.resolve_all(&local_username()))
+}
+
+/// 当前用户主目录
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+fn local_username() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+impl OpenSSHConfig {
+    /// 读取并解析配置文件（含 Include 的文件）
+    pub fn load(path: &Path, home: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Ok(Self::parse(&content, home))
+    }
+
+    /// 解析配置文本，相对 Include 路径基于 ~/.ssh
+    pub fn parse(content: &str, home: &Path) -> Self {
+        let mut config = Self {
+            blocks: vec![Block::new(Some(vec!["*".to_string()]), None)],
+            home: home.to_path_buf(),
+            unmapped: Vec::new(),
+        };
+        config.parse_content(content, 0, None);
+        config
+    }
+
+    /// 解析配置文本，parent 为 Include 所在的外层块
+    fn parse_content(&mut self, content: &str, depth: usize, parent: Option<usize>) {
+        for (number, line) in content.lines().enumerate() {
+            let Some((keyword, args)) = split_line(line) else {
+                continue;
+            };
+
+            match keyword.as_str() {
+                "host" => self.blocks.push(Block::new(Some(args), parent)),
+                "match" => {
+                    self.unmapped.push(UnmappedEntry {
+                        host: format!("Match {}", args.join(" ")),
+                        reason: "Match blocks are not supported".to_string(),
+                    });
+                    self.blocks.push(Block::new(None, parent));
+                }
+                "include" => self.include(&args, depth),
+                _ if args.is_empty() => self.unmapped.push(UnmappedEntry {
+                    host: format!("line {}", number + 1),
+                    reason: format!("Missing argument for {}", keyword),
+                }),
+                _ => {
+                    if let Some(block) = self.blocks.last_mut() {
+                        block.options.push((keyword, args));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 就地展开 Include（支持 ~ 和文件名中的通配符）
+    /// - 被包含文件中的内容只在 Include 所在块匹配时生效
+    /// - 每个文件从所在块开始，文件结束后恢复所在块，之后的选项仍属于所在块
+    fn include(&mut self, args: &[String], depth: usize) {
+        if depth >= MAX_INCLUDE_DEPTH {
+            self.unmapped.push(UnmappedEntry {
+                host: format!("Include {}", args.join(" ")),
+                reason: "Include nested too deeply".to_string(),
+            });
+            return;
+        }
+
+        let enclosing = self.blocks.len() - 1;
+        for arg in args {
+            let pattern = expand_tilde(arg, &self.home);
+            let pattern = if pattern.is_absolute() {
+                pattern
+            } else {
+                self.home.join(".ssh").join(pattern)
+            };
+
+            for path in expand_glob(&pattern) {
+                match std::fs::read_to_string(&path) {
+                    Ok(content) => {
+                        self.blocks.push(Block::continuation(enclosing));
+                        self.parse_content(&content, depth + 1, Some(enclosing));
+                        self.blocks.push(Block::continuation(enclosing));
+                    }
+                    Err(e) => self.unmapped.push(UnmappedEntry {
+                        host: format!("Include {}", path.display()),
+                        reason: format!("Failed to read included file: {}", e),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// 块是否作用于主机：自身的 Host 模式和所有外层块都需要匹配
+    fn block_matches(&self, index: usize, host: &str) -> bool {
+        let mut current = Some(index);
+        while let Some(index) = current {
+            let block = &self.blocks[index];
+            match &block.patterns {
+                Some(patterns) if host_matches(patterns, host) => {}
+                _ => return false,
+            }
+            current = block.parent;
+        }
+        true
+    }
+
+    /// 按 OpenSSH 规则收集某个主机的选项：每个选项取第一次出现的值，IdentityFile 等累加
+    fn options_for(&self, host: &str) -> HashMap<String, Vec<String>> {
+        let mut options: HashMap<String, Vec<String>> = HashMap::new();
+        let matching = (0..self.blocks.len()).filter(|&index| self.block_matches(index, host));
+        for block in matching.map(|index| &self.blocks[index]) {
+            for (keyword, args) in &block.options {
+                match keyword.as_str() {
+                    "identityfile" | "certificatefile" => options
+                        .entry(keyword.clone())
+                        .or_default()
+                        .extend(args.clone()),
+                    _ => {
+                        options
+                            .entry(keyword.clone())
+                            .or_insert_with(|| args.clone());
+                    }
+                }
+            }
+        }
+        options
+    }
+
+    /// Host 行中不含通配符的别名（按出现顺序去重）
+    /// - Include 所在块不匹配该别名时，被包含的 Host 块对它不生效，不作为连接导入
+    fn concrete_hosts(&self) -> Vec<String> {
+        let mut hosts: Vec<String> = Vec::new();
+        for (index, block) in self.blocks.iter().enumerate() {
+            let Some(patterns) = &block.patterns else {
+                continue;
+            };
+            for pattern in patterns {
+                if !is_wildcard(pattern)
+                    && !pattern.starts_with('!')
+                    && !hosts.contains(pattern)
+                    && self.block_matches(index, pattern)
+                {
+                    hosts.push(pattern.clone());
+                }
+            }
+        }
+        hosts
+    }
+
+    /// 将所有具体的 Host 别名转换为连接配置
+    pub fn resolve_all(&self, local_user: &str) -> ImportReport {
+        let mut report = ImportReport {
+            hosts: Vec::new(),
+            unmapped: self.unmapped.clone(),
+        };
+
+        // 只有通配符的 Host 块不会生成连接，其选项作为默认值作用于匹配的主机
+        for patterns in self
+            .blocks
+            .iter()
+            .skip(1)
+            .filter(|b| !b.continuation)
+            .filter_map(|b| b.patterns.as_ref())
+        {
+            if !patterns.is_empty()
+                && patterns
+                    .iter()
+                    .all(|p| is_wildcard(p) || p.starts_with('!'))
+            {
+                report.unmapped.push(UnmappedEntry {
+                    host: patterns.join(" "),
+                    reason: "Wildcard pattern applied as defaults to matching hosts".to_string(),
+                });
+            }
+        }
+
+        for alias in self.concrete_hosts() {
+            match self.resolve(&alias, local_user) {
+                Ok(host) => report.hosts.push(host),
+                Err(e) => report.unmapped.push(UnmappedEntry {
+                    host: alias,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+
+        report
+    }
+
+    /// 解析单个主机别名
+    pub fn resolve(&self, alias: &str, local_user: &str) -> Result<ImportedHost> {
+        let options = self.options_for(alias);
+        if options.contains_key("proxycommand") && !options.contains_key("proxyjump") {
+            return Err(anyhow!("ProxyCommand is not supported"));
+        }
+
+        let target = self.resolve_target(alias, None, None, &options, local_user)?;
+        let jump_host = match options.get("proxyjump") {
+            Some(args) => self.resolve_jump_chain(&args.join(" "), local_user, 0)?,
+            None => None,
+        };
+
+        let mut ignored_options: Vec<String> = options
+            .iter()
+            .filter(|(keyword, _)| !SUPPORTED_OPTIONS.contains(&keyword.as_str()))
+            .map(|(keyword, args)| format!("{} {}", keyword, args.join(" ")))
+            .collect();
+        ignored_options.sort();
+        if target.identity_files.len() > 1 {
+            ignored_options.push(format!(
+                "identityfile {} (only the first IdentityFile is used)",
+                target.identity_files[1..].join(" ")
+            ));
+        }
+
+        let private_key_path = target.identity_files.into_iter().next();
+        Ok(ImportedHost {
+            name: alias.to_string(),
+            config: SSHConfig {
+                host: target.host,
+                port: target.port,
+                username: target.user,
+                auth_method: auth_method_for(&private_key_path),
+                password: None,
+                private_key_path,
+                passphrase: None,
+                certificate_path: target.certificate_file,
+                jump_host: jump_host.map(|hop| *hop),
+            },
+            ignored_options,
+        })
+    }
+
+    /// 解析 ProxyJump（逗号分隔，按连接顺序），返回最后一跳，前面的跳板嵌套在其 jump_host 中
+    fn resolve_jump_chain(
+        &self,
+        value: &str,
+        local_user: &str,
+        depth: usize,
+    ) -> Result<Option<Box<JumpHostConfig>>> {
+        if value.eq_ignore_ascii_case("none") {
+            return Ok(None);
+        }
+        if depth >= MAX_JUMP_DEPTH {
+            return Err(anyhow!("ProxyJump chain is too deep"));
+        }
+
+        let mut previous: Option<Box<JumpHostConfig>> = None;
+        for (index, spec) in value.split(',').map(str::trim).enumerate() {
+            let (user, host, port) = parse_jump_spec(spec)?;
+            let options = self.options_for(&host);
+            let target = self.resolve_target(&host, user, port, &options, local_user)?;
+
+            // 只有第一跳使用它自己的 ProxyJump，后续跳板经由前一跳连接
+            if index == 0 {
+                if let Some(args) = options.get("proxyjump") {
+                    previous = self.resolve_jump_chain(&args.join(" "), local_user, depth + 1)?;
+                }
+            }
+
+            let private_key_path = target.identity_files.into_iter().next();
+            previous = Some(Box::new(JumpHostConfig {
+                host: target.host,
+                port: target.port,
+                username: target.user,
+                auth_method: auth_method_for(&private_key_path),
+                password: None,
+                private_key_path,
+                passphrase: None,
+                certificate_path: target.certificate_file,
+                jump_host: previous,
+            }));
+        }
+
+        Ok(previous)
+    }
+
+    /// 计算主机地址、端口、用户和密钥路径（user/port 为 ProxyJump 中显式指定的值）
+    fn resolve_target(
+        &self,
+        alias: &str,
+        user: Option<String>,
+        port: Option<u16>,
+        options: &HashMap<String, Vec<String>>,
+        local_user: &str,
+    ) -> Result<ResolvedTarget> {
+        let first = |keyword: &str| options.get(keyword).and_then(|args| args.first());
+
+        let mut tokens = Tokens {
+            alias,
+            host: alias,
+            user: "",
+            port: 22,
+            local_user,
+            home: &self.home,
+        };
+        let host = first("hostname")
+            .map(|hostname| expand_tokens(hostname, &tokens))
+            .unwrap_or_else(|| alias.to_string());
+
+        let port = match port {
+            Some(port) => port,
+            None => match first("port") {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid Port: {}", value))?,
+                None => 22,
+            },
+        };
+
+        let user = user
+            .or_else(|| first("user").map(|u| expand_tokens(u, &tokens)))
+            .unwrap_or_else(|| local_user.to_string());
+        if user.is_empty() {
+            return Err(anyhow!("No User specified and local user name is unknown"));
+        }
+
+        tokens.host = &host;
+        tokens.user = &user;
+        tokens.port = port;
+        let expand_path = |value: &String| {
+            expand_tilde(&expand_tokens(value, &tokens), &self.home)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        let identity_files = options
+            .get("identityfile")
+            .map(|files| {
+                files
+                    .iter()
+                    .filter(|f| !f.eq_ignore_ascii_case("none"))
+                    .map(expand_path)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let certificate_file = options
+            .get("certificatefile")
+            .and_then(|files| files.first())
+            .map(expand_path);
+
+        Ok(ResolvedTarget {
+            host,
+            port,
+            user,
+            identity_files,
+            certificate_file,
+        })
+    }
+}
+
+struct ResolvedTarget {
+    host: String,
+    port: u16,
+    user: String,
+    identity_files: Vec<String>,
+    certificate_file: Option<String>,
+}
+
+/// 有 IdentityFile 时使用密钥认证，否则交给 ssh-agent（与 OpenSSH 的默认行为最接近）
+fn auth_method_for(private_key_path: &Option<String>) -> AuthMethod {
+    if private_key_path.is_some() {
+        AuthMethod::Key
+    } else {
+        AuthMethod::Agent
+    }
+}
+
+/// 拆分一行为（小写关键字，参数列表），关键字与参数间可用空白或 '=' 分隔
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or(line.len());
+    let keyword = line[..end].to_lowercase();
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    Some((keyword, split_args(rest)))
+}
+
+/// 按空白拆分参数，支持双引号包裹含空格的参数，遇到未加引号的 # 视为注释
+fn split_args(value: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+
+    for c in value.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            '#' if !in_quotes && !has_arg => break,
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Host 模式列表匹配：任一否定模式匹配则不匹配，否则任一肯定模式匹配即可
+fn host_matches(patterns: &[String], host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if wildcard_match(&negated.to_lowercase(), &host.to_lowercase()) {
+                return false;
+            }
+        } else if wildcard_match(&pattern.to_lowercase(), &host.to_lowercase()) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+fn is_wildcard(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// `*` 匹配任意字符串，`?` 匹配单个字符
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 展开文件名中的通配符（目录部分需为字面路径），结果按文件名排序
+fn expand_glob(pattern: &Path) -> Vec<PathBuf> {
+    let file_name = pattern
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if !is_wildcard(&file_name) {
+        return vec![pattern.to_path_buf()];
+    }
+
+    let Some(dir) = pattern.parent() else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter(|entry| wildcard_match(&file_name, &entry.file_name().to_string_lossy()))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+/// 展开开头的 ~ 或 ~/
+fn expand_tilde(path: &str, home: &Path) -> PathBuf {
+    if path == "~" {
+        home.to_path_buf()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        home.join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// 可在 HostName、User、IdentityFile 中使用的 % 变量
+struct Tokens<'a> {
+    alias: &'a str,
+    host: &'a str,
+    user: &'a str,
+    port: u16,
+    local_user: &'a str,
+    home: &'a Path,
+}
+
+/// 展开 %h %n %r %p %u %d %%，未知变量原样保留
+fn expand_tokens(value: &str, tokens: &Tokens) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => result.push('%'),
+            Some('h') => result.push_str(tokens.host),
+            Some('n') => result.push_str(tokens.alias),
+            Some('r') => result.push_str(tokens.user),
+            Some('p') => result.push_str(&tokens.port.to_string()),
+            Some('u') => result.push_str(tokens.local_user),
+            Some('d') => result.push_str(&tokens.home.to_string_lossy()),
+            Some(other) => {
+                result.push('%');
+                result.push(other);
+            }
+            None => result.push('%'),
+        }
+    }
+    result
+}
+
+/// 解析 ProxyJump 的单跳：[user@]host[:port] 或 ssh://[user@]host[:port]
+fn parse_jump_spec(spec: &str) -> Result<(Option<String>, String, Option<u16>)> {
+    let spec = spec.strip_prefix("ssh://").unwrap_or(spec);
+    let (user, rest) = match spec.rsplit_once('@') {
+        Some((user, rest)) => (Some(user.to_string()), rest),
+        None => (None, spec),
+    };
+
+    let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+        // [IPv6]:port
+        let (host, tail) = bracketed
+            .split_once(']')
+            .ok_or_else(|| anyhow!("Invalid ProxyJump host: {}", spec))?;
+        (host, tail.strip_prefix(':'))
+    } else {
+        match rest.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') => (host, Some(port)),
+            _ => (rest, None),
+        }
+    };
+
+    if host.is_empty() {
+        return Err(anyhow!("Invalid ProxyJump host: {}", spec));
+    }
+    let port = port
+        .map(|p| {
+            p.parse()
+                .map_err(|_| anyhow!("Invalid ProxyJump port: {}", spec))
+        })
+        .transpose()?;
+
+    Ok((user, host.to_string(), port))
+}
+
+/// 导出时连接端点的统一视图（SSHConfig 与 JumpHostConfig 共用）
+struct Endpoint<'a> {
+    host: &'a str,
+    port: u16,
+    username: &'a str,
+    auth_method: &'a AuthMethod,
+    private_key_path: Option<&'a str>,
+    certificate_path: Option<&'a str>,
+    jump_host: Option<&'a JumpHostConfig>,
+}
+
+impl<'a> From<&'a SSHConfig> for Endpoint<'a> {
+    fn from(config: &'a SSHConfig) -> Self {
+        Self {
+            host: &config.host,
+            port: config.port,
+            username: &config.username,
+            auth_method: &config.auth_method,
+            private_key_path: config.private_key_path.as_deref(),
+            certificate_path: config.certificate_path.as_deref(),
+            jump_host: config.jump_host.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<&'a JumpHostConfig> for Endpoint<'a> {
+    fn from(config: &'a JumpHostConfig) -> Self {
+        Self {
+            host: &config.host,
+            port: config.port,
+            username: &config.username,
+            auth_method: &config.auth_method,
+            private_key_path: config.private_key_path.as_deref(),
+            certificate_path: config.certificate_path.as_deref(),
+            jump_host: config.jump_host.as_deref(),
+        }
+    }
+}
+
+impl Endpoint<'_> {
+    /// 地址、用户、密钥以及整条跳板链都相同
+    fn same_as(&self, other: &Endpoint) -> bool {
+        self.host == other.host
+            && self.port == other.port
+            && self.username == other.username
+            && self.private_key_path == other.private_key_path
+            && self.certificate_path == other.certificate_path
+            && match (self.jump_host, other.jump_host) {
+                (None, None) => true,
+                (Some(a), Some(b)) => Endpoint::from(a).same_as(&Endpoint::from(b)),
+                _ => false,
+            }
+    }
+
+    /// HostName/Port/User 等选项（不含 ProxyJump），密码和口令不会导出
+    fn write_options(&self, out: &mut String) -> Result<()> {
+        out.push_str(&format!("    HostName {}\n", quote_arg(self.host)?));
+        if self.port != 22 {
+            out.push_str(&format!("    Port {}\n", self.port));
+        }
+        out.push_str(&format!("    User {}\n", quote_arg(self.username)?));
+        match self.auth_method {
+            AuthMethod::Password => {
+                out.push_str("    PreferredAuthentications password,keyboard-interactive\n")
+            }
+            AuthMethod::KeyboardInteractive => {
+                out.push_str("    PreferredAuthentications keyboard-interactive\n")
+            }
+            AuthMethod::Key | AuthMethod::Agent => {}
+        }
+        if let Some(key) = self.private_key_path {
+            out.push_str(&format!("    IdentityFile {}\n", quote_arg(key)?));
+        }
+        if let Some(cert) = self.certificate_path {
+            out.push_str(&format!("    CertificateFile {}\n", quote_arg(cert)?));
+        }
+        Ok(())
+    }
+
+    /// 检查各项值能否写入 ssh config
+    fn check(&self) -> Result<()> {
+        self.write_options(&mut String::new())
+    }
+
+    /// 使用默认认证（公钥/agent）且不需要密钥文件的跳板可直接写成 user@host[:port]
+    /// - 密码/键盘交互认证需要写 PreferredAuthentications，需要单独的 Host 块
+    /// - 用户名或地址含有 ProxyJump 中有特殊含义的字符时同样需要单独的 Host 块
+    fn needs_alias(&self) -> bool {
+        self.private_key_path.is_some()
+            || self.certificate_path.is_some()
+            || matches!(
+                self.auth_method,
+                AuthMethod::Password | AuthMethod::KeyboardInteractive
+            )
+            || !is_plain_jump_part(self.username)
+            || !is_plain_jump_part(self.host)
+    }
+
+    fn jump_spec(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.to_string()
+        };
+        if self.port == 22 {
+            format!("{}@{}", self.username, host)
+        } else {
+            format!("{}@{}:{}", self.username, host, self.port)
+        }
+    }
+}
+
+/// 检查连接（含跳板链）能否导出为 ssh config，导出前用于跳过无法导出的连接
+pub fn check_exportable(config: &SSHConfig) -> Result<()> {
+    Endpoint::from(config).check()?;
+    let hops = std::iter::successors(config.jump_host.as_ref(), |hop| hop.jump_host.as_deref());
+    for hop in hops {
+        Endpoint::from(hop).check()?;
+    }
+    Ok(())
+}
+
+/// 将 SSH 连接（名称，配置）导出为 ssh config 文本
+/// - 跳板机导出为 ProxyJump；与某个导出连接完全相同的跳板直接引用其 Host 别名
+/// - 需要密钥或密码/键盘交互认证的跳板额外生成 `<别名>-jumpN` 的 Host 块
+/// - 含有无法写入 ssh config 的值（双引号、换行等）时返回错误，可先用 check_exportable 过滤
+pub fn export(connections: &[(String, SSHConfig)]) -> Result<String> {
+    let mut used = HashSet::new();
+    let aliases: Vec<String> = connections
+        .iter()
+        .map(|(name, _)| unique_alias(&sanitize_alias(name), &mut used))
+        .collect();
+
+    let mut out = String::new();
+    for (index, (name, config)) in connections.iter().enumerate() {
+        let alias = &aliases[index];
+        let endpoint = Endpoint::from(config);
+
+        // 跳板按连接顺序排列（最先连接的在前）
+        let mut hops: Vec<&JumpHostConfig> =
+            std::iter::successors(config.jump_host.as_ref(), |hop| hop.jump_host.as_deref())
+                .collect();
+        hops.reverse();
+
+        // 从离目标最近的跳板开始，找到与其他导出连接完全相同的一跳，其前面的跳板由该连接自身的 ProxyJump 覆盖
+        let mut proxy_jump = Vec::new();
+        let mut first_hop = 0;
+        for (position, hop) in hops.iter().enumerate().rev() {
+            let hop = Endpoint::from(*hop);
+            let matched = connections
+                .iter()
+                .enumerate()
+                .find(|(other, (_, c))| *other != index && hop.same_as(&Endpoint::from(c)));
+            if let Some((other, _)) = matched {
+                proxy_jump.push(aliases[other].clone());
+                first_hop = position + 1;
+                break;
+            }
+        }
+
+        for (position, hop) in hops.iter().enumerate().skip(first_hop) {
+            let hop = Endpoint::from(*hop);
+            if hop.needs_alias() {
+                let hop_alias = unique_alias(&format!("{}-jump{}", alias, position + 1), &mut used);
+                out.push_str(&format!("Host {}\n", hop_alias));
+                hop.write_options(&mut out)?;
+                out.push('\n');
+                proxy_jump.push(hop_alias);
+            } else {
+                proxy_jump.push(hop.jump_spec());
+            }
+        }
+
+        if alias != name {
+            out.push_str(&format!("# {}\n", comment_text(name)));
+        }
+        out.push_str(&format!("Host {}\n", alias));
+        endpoint.write_options(&mut out)?;
+        if !proxy_jump.is_empty() {
+            out.push_str(&format!("    ProxyJump {}\n", proxy_jump.join(",")));
+        }
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Host 别名不能包含空白和通配符
+fn sanitize_alias(name: &str) -> String {
+    let alias: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "._-@+".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if alias.is_empty() {
+        "host".to_string()
+    } else {
+        alias
+    }
+}
+
+/// 别名重复时追加 -2、-3 ...
+fn unique_alias(alias: &str, used: &mut HashSet<String>) -> String {
+    let mut candidate = alias.to_string();
+    let mut suffix = 2;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = format!("{}-{}", alias, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// 含空白的参数加双引号
+/// - ssh config 的参数无法转义双引号，含双引号或换行等控制字符的值会改变配置结构，直接拒绝
+fn quote_arg(value: &str) -> Result<String> {
+    if value.contains(|c: char| c == '"' || c.is_control()) {
+        return Err(anyhow!(
+            "Value cannot be written to ssh config: {:?}",
+            value
+        ));
+    }
+    if value.contains(char::is_whitespace) {
+        Ok(format!("\"{}\"", value))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+/// 可以直接写在 ProxyJump 中的用户名或地址（不含空白、逗号、引号、@ 等）
+fn is_plain_jump_part(value: &str) -> bool {
+    !value.is_empty()
+        && !value.contains(|c: char| c.is_whitespace() || c.is_control() || ",\"@[]".contains(c))
+}
+
+/// 写入注释的文本（控制字符替换为空格，避免换行后的内容被当作配置）
+pub fn comment_text(text: &str) -> String {
+    text.replace(|c: char| c.is_control(), " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> OpenSSHConfig {
+        OpenSSHConfig::parse(content, Path::new("/home/alice"))
+    }
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(
+            split_line("  HostName=example.com"),
+            Some(("hostname".to_string(), vec!["example.com".to_string()]))
+        );
+        assert_eq!(
+            split_line("IdentityFile \"~/my keys/id\" # comment"),
+            Some(("identityfile".to_string(), vec!["~/my keys/id".to_string()]))
+        );
+        assert_eq!(split_line("# Host foo"), None);
+        assert_eq!(split_line("   "), None);
+    }
+
+    #[test]
+    fn test_host_matches() {
+        let patterns = vec!["*.example.com".to_string(), "!bad.example.com".to_string()];
+        assert!(host_matches(&patterns, "web.EXAMPLE.com"));
+        assert!(!host_matches(&patterns, "bad.example.com"));
+        assert!(!host_matches(&patterns, "example.org"));
+        assert!(host_matches(&["db?".to_string()], "db1"));
+        assert!(!host_matches(&["db?".to_string()], "db10"));
+    }
+
+    #[test]
+    fn test_resolve_first_value_wins() {
+        let config = parse(
+            "User global\n\
+             Host web\n  HostName web.internal\n  IdentityFile ~/.ssh/web\n\
+             Host *\n  User fallback\n  Port 2222\n  IdentityFile ~/.ssh/%r@%h\n  ForwardAgent yes\n",
+        );
+        let host = config.resolve("web", "alice").unwrap();
+        assert_eq!(host.config.host, "web.internal");
+        assert_eq!(host.config.port, 2222);
+        assert_eq!(host.config.username, "global");
+        assert_eq!(host.config.auth_method.as_str(), "key");
+        assert_eq!(
+            host.config.private_key_path.as_deref(),
+            Some("/home/alice/.ssh/web")
+        );
+        assert_eq!(host.ignored_options.len(), 2);
+        assert!(host.ignored_options[0].starts_with("forwardagent"));
+        assert!(host.ignored_options[1].contains("/home/alice/.ssh/global@web.internal"));
+    }
+
+    #[test]
+    fn test_resolve_all_reports_unmapped() {
+        let config = parse(
+            "Host a b *.lan\n  User ops\n\
+             Host *.lan\n  Port 2200\n\
+             Match host c\n  User nobody\n\
+             Host legacy\n  ProxyCommand nc %h %p\n",
+        );
+        let report = config.resolve_all("alice");
+        let names: Vec<&str> = report.hosts.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(report.hosts[0].config.auth_method.as_str(), "agent");
+
+        let reasons: Vec<(&str, &str)> = report
+            .unmapped
+            .iter()
+            .map(|u| (u.host.as_str(), u.reason.as_str()))
+            .collect();
+        assert!(reasons.contains(&("Match host c", "Match blocks are not supported")));
+        assert!(reasons.contains(&("legacy", "ProxyCommand is not supported")));
+        assert!(reasons.iter().any(|(host, _)| *host == "*.lan"));
+    }
+
+    #[test]
+    fn test_proxy_jump_chain() {
+        let config = parse(
+            "Host bastion\n  HostName bastion.example.com\n  User ops\n  Port 2222\n\
+             Host inner\n  HostName 10.0.0.2\n  ProxyJump bastion\n\
+             Host app\n  HostName 10.0.1.5\n  User deploy\n  ProxyJump inner,root@[fd00::1]:22\n",
+        );
+        let host = config.resolve("app", "alice").unwrap();
+
+        // 连接顺序：bastion -> inner -> fd00::1 -> app
+        let last = host.config.jump_host.unwrap();
+        assert_eq!(last.host, "fd00::1");
+        assert_eq!(last.username, "root");
+        let inner = last.jump_host.unwrap();
+        assert_eq!(inner.host, "10.0.0.2");
+        assert_eq!(inner.username, "alice");
+        let bastion = inner.jump_host.unwrap();
+        assert_eq!(bastion.host, "bastion.example.com");
+        assert_eq!(bastion.port, 2222);
+        assert!(bastion.jump_host.is_none());
+    }
+
+    #[test]
+    fn test_proxy_jump_loop_is_reported() {
+        let config = parse("Host a\n  ProxyJump b\nHost b\n  ProxyJump a\n");
+        assert!(config.resolve("a", "alice").is_err());
+    }
+
+    #[test]
+    fn test_parse_jump_spec() {
+        assert_eq!(
+            parse_jump_spec("ssh://ops@gw:2200").unwrap(),
+            (Some("ops".to_string()), "gw".to_string(), Some(2200))
+        );
+        assert_eq!(
+            parse_jump_spec("gw").unwrap(),
+            (None, "gw".to_string(), None)
+        );
+        assert!(parse_jump_spec("gw:port").is_err());
+    }
+
+    #[test]
+    fn test_include_with_glob() {
+        let home = std::env::temp_dir().join(format!("devhub-ssh-config-{}", uuid::Uuid::new_v4()));
+        let conf_d = home.join(".ssh").join("config.d");
+        std::fs::create_dir_all(&conf_d).unwrap();
+        std::fs::write(
+            conf_d.join("10-db.conf"),
+            "Host db\n  HostName db.internal\n",
+        )
+        .unwrap();
+        std::fs::write(conf_d.join("20-cache.conf"), "Host cache\n  Port 2022\n").unwrap();
+        std::fs::write(conf_d.join("notes.txt"), "Host ignored\n").unwrap();
+
+        let config = OpenSSHConfig::parse("Include config.d/*.conf\nHost web\n", &home);
+        let report = config.resolve_all("alice");
+        std::fs::remove_dir_all(&home).unwrap();
+
+        let names: Vec<&str> = report.hosts.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["db", "cache", "web"]);
+        assert_eq!(report.hosts[0].config.host, "db.internal");
+        assert_eq!(report.hosts[1].config.port, 2022);
+    }
+
+    #[test]
+    fn test_include_inside_host_block() {
+        let home = std::env::temp_dir().join(format!("devhub-ssh-config-{}", uuid::Uuid::new_v4()));
+        let ssh_dir = home.join(".ssh");
+        std::fs::create_dir_all(&ssh_dir).unwrap();
+        std::fs::write(
+            ssh_dir.join("web.conf"),
+            "Port 2200\nHost web-admin\n  HostName admin.internal\n",
+        )
+        .unwrap();
+        std::fs::write(
+            ssh_dir.join("common.conf"),
+            "Host db\n  HostName db.internal\n",
+        )
+        .unwrap();
+
+        let config = OpenSSHConfig::parse(
+            "Host web\n  Include web.conf\n  User deploy\n\
+             Host *\n  Include common.conf\n",
+            &home,
+        );
+        let report = config.resolve_all("alice");
+        std::fs::remove_dir_all(&home).unwrap();
+
+        // web.conf 中的 Host 块只在 Host web 匹配时生效，web-admin 不会匹配
+        let names: Vec<&str> = report.hosts.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "db"]);
+
+        // Include 之后的选项仍属于 Host web
+        assert_eq!(report.hosts[0].config.port, 2200);
+        assert_eq!(report.hosts[0].config.username, "deploy");
+        assert_eq!(report.hosts[1].config.host, "db.internal");
+        assert_eq!(report.hosts[1].config.username, "alice");
+    }
+
+    fn ssh_config(host: &str, username: &str, jump_host: Option<JumpHostConfig>) -> SSHConfig {
+        SSHConfig {
+            host: host.to_string(),
+            port: 22,
+            username: username.to_string(),
+            auth_method: AuthMethod::Agent,
+            password: None,
+            private_key_path: None,
+            passphrase: None,
+            certificate_path: None,
+            jump_host,
+        }
+    }
+
+    fn jump(host: &str, port: u16, key: Option<&str>) -> JumpHostConfig {
+        JumpHostConfig {
+            host: host.to_string(),
+            port,
+            username: "ops".to_string(),
+            auth_method: AuthMethod::Key,
+            password: None,
+            private_key_path: key.map(str::to_string),
+            passphrase: None,
+            certificate_path: None,
+            jump_host: None,
+        }
+    }
+
+    #[test]
+    fn test_export_references_existing_alias() {
+        let mut bastion = ssh_config("bastion.example.com", "ops", None);
+        bastion.port = 2222;
+        bastion.auth_method = AuthMethod::Key;
+        bastion.private_key_path = Some("/keys/ops key".to_string());
+
+        let mut hop = jump("bastion.example.com", 2222, Some("/keys/ops key"));
+        hop.auth_method = AuthMethod::Key;
+        let connections = vec![
+            ("bastion".to_string(), bastion),
+            (
+                "App Server".to_string(),
+                ssh_config("10.0.1.5", "deploy", Some(hop)),
+            ),
+        ];
+
+        let text = export(&connections).unwrap();
+        assert!(text.contains("IdentityFile \"/keys/ops key\"\n"));
+        assert!(text.contains("# App Server\nHost App-Server\n"));
+        assert!(text.contains("    ProxyJump bastion\n"));
+        assert!(!text.contains("jump1"));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let mut inner = jump("10.0.0.2", 22, Some("/keys/inner"));
+        inner.jump_host = Some(Box::new(jump("fd00::1", 2200, None)));
+        let mut password = ssh_config("10.0.1.5", "deploy", Some(inner));
+        password.auth_method = AuthMethod::Password;
+        password.password = Some("secret".to_string());
+        let connections = vec![("app".to_string(), password)];
+
+        let text = export(&connections).unwrap();
+        assert!(!text.contains("secret"));
+        assert!(text.contains("ProxyJump ops@[fd00::1]:2200,app-jump2\n"));
+
+        let config = parse(&text);
+        let host = config.resolve("app", "alice").unwrap();
+        assert_eq!(host.config.host, "10.0.1.5");
+        let inner = host.config.jump_host.unwrap();
+        assert_eq!(inner.host, "10.0.0.2");
+        assert_eq!(inner.private_key_path.as_deref(), Some("/keys/inner"));
+        let outer = inner.jump_host.unwrap();
+        assert_eq!((outer.host.as_str(), outer.port), ("fd00::1", 2200));
+    }
+
+    #[test]
+    fn test_export_rejects_injected_values() {
+        let mut config = ssh_config("10.0.1.5", "deploy", None);
+        config.private_key_path = Some("/keys/id\"\n    ProxyCommand sh -c evil".to_string());
+        assert!(check_exportable(&config).is_err());
+        assert!(export(&[("app".to_string(), config)]).is_err());
+
+        let mut hop = jump("10.0.0.2", 22, None);
+        hop.username = "ops\nProxyCommand evil".to_string();
+        let config = ssh_config("10.0.1.5", "deploy", Some(hop));
+        assert!(check_exportable(&config).is_err());
+        assert!(export(&[("app".to_string(), config)]).is_err());
+
+        // 名称只出现在注释中，换行被替换
+        let config = ssh_config("10.0.1.5", "deploy", None);
+        let text = export(&[("app\nProxyCommand evil".to_string(), config)]).unwrap();
+        assert!(text.contains("# app ProxyCommand evil\n"));
+        assert!(!text.contains("\nProxyCommand"));
+    }
+
+    #[test]
+    fn test_export_inline_hop_keeps_auth_method() {
+        let mut hop = jump("10.0.0.2", 22, None);
+        hop.auth_method = AuthMethod::Password;
+        let connections = vec![(
+            "app".to_string(),
+            ssh_config("10.0.1.5", "deploy", Some(hop)),
+        )];
+
+        let text = export(&connections).unwrap();
+        assert!(text.contains("Host app-jump1\n    HostName 10.0.0.2\n    User ops\n    PreferredAuthentications password,keyboard-interactive\n"));
+        assert!(text.contains("    ProxyJump app-jump1\n"));
+
+        // 默认认证的跳板仍写成 user@host
+        let connections = vec![(
+            "app".to_string(),
+            ssh_config("10.0.1.5", "deploy", Some(jump("10.0.0.2", 22, None))),
+        )];
+        let text = export(&connections).unwrap();
+        assert!(text.contains("    ProxyJump ops@10.0.0.2\n"));
+    }
+
+    #[test]
+    fn test_unique_alias() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_alias(&sanitize_alias("web *"), &mut used), "web--");
+        assert_eq!(unique_alias(&sanitize_alias("web ?"), &mut used), "web---2");
+        assert_eq!(unique_alias(&sanitize_alias("  "), &mut used), "host");
+    }
+}
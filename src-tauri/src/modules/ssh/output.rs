@@ -0,0 +1,299 @@
+use super::scrollback::Scrollback;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::sync::Notify;
+
+/// 终端输出的批量发送与流控选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputOptions {
+    /// 输出在缓冲区中最多停留的时间（毫秒），0 表示收到即发送
+    pub flush_interval_ms: u64,
+    /// 缓冲区达到该字节数时立即发送
+    pub flush_bytes: usize,
+    /// 是否启用流控（前端通过 ssh_ack_output 确认已处理的字节数）
+    pub flow_control: bool,
+    /// 未确认字节数达到该值时暂停读取输出
+    pub high_watermark: usize,
+    /// 未确认字节数降到该值以下时恢复读取
+    pub low_watermark: usize,
+    /// 服务端回滚缓冲区保留的字节数，0 表示不保留
+    pub scrollback_bytes: usize,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: 16,
+            flush_bytes: 64 * 1024,
+            flow_control: true,
+            high_watermark: 2 * 1024 * 1024,
+            low_watermark: 512 * 1024,
+            scrollback_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// 输出缓冲：合并短时间内收到的多个数据包，减少发送给前端的事件数
+pub struct OutputBatcher {
+    buffer: Vec<u8>,
+    /// 缓冲区中最早的数据到达的时间
+    since: Option<Instant>,
+    interval: Duration,
+    max_bytes: usize,
+}
+
+impl OutputBatcher {
+    pub fn new(options: &OutputOptions) -> Self {
+        Self {
+            buffer: Vec::new(),
+            since: None,
+            interval: Duration::from_millis(options.flush_interval_ms),
+            max_bytes: options.flush_bytes.max(1),
+        }
+    }
+
+    /// 追加数据，返回是否需要立即发送
+    pub fn push(&mut self, data: &[u8]) -> bool {
+        if self.buffer.is_empty() {
+            self.since = Some(Instant::now());
+        }
+        self.buffer.extend_from_slice(data);
+        self.buffer.len() >= self.max_bytes || self.interval.is_zero()
+    }
+
+    /// 缓冲区需要发送的截止时间（缓冲区为空时为 None）
+    pub fn deadline(&self) -> Option<Instant> {
+        self.since.map(|since| since + self.interval)
+    }
+
+    /// 取出缓冲区中的全部数据
+    pub fn take(&mut self) -> Vec<u8> {
+        self.since = None;
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+#[derive(Debug, Default)]
+struct FlowState {
+    /// 已发送但前端尚未确认的字节数
+    unacked: usize,
+    /// 前端是否确认过（不确认的前端不受流控影响）
+    acked: bool,
+    paused: bool,
+    closed: bool,
+}
+
+/// 基于前端确认的输出流控
+/// - 未确认字节数超过高水位时读取任务暂停读取 channel，直到前端确认到低水位以下（不会超时恢复）
+/// - russh 0.45 收到数据即按固定大小补充 SSH 窗口，无法对单个 channel 停止补充，
+///   暂停期间服务器继续发送的数据在 russh 的 channel 队列中排队，恢复后按顺序读取
+pub struct FlowControl {
+    state: Mutex<FlowState>,
+    notify: Notify,
+    enabled: bool,
+    high_watermark: usize,
+    low_watermark: usize,
+}
+
+impl FlowControl {
+    pub fn new(options: &OutputOptions) -> Self {
+        let high_watermark = options.high_watermark.max(1);
+        Self {
+            state: Mutex::new(FlowState::default()),
+            notify: Notify::new(),
+            enabled: options.flow_control,
+            high_watermark,
+            low_watermark: options.low_watermark.min(high_watermark),
+        }
+    }
+
+    /// 记录已发送的字节数
+    pub fn sent(&self, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.unacked = state.unacked.saturating_add(bytes);
+    }
+
+    /// 前端确认已处理的字节数
+    pub fn ack(&self, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.unacked = state.unacked.saturating_sub(bytes);
+        state.acked = true;
+        if state.paused && state.unacked <= self.low_watermark {
+            state.paused = false;
+            self.notify.notify_waiters();
+        }
+    }
+
+    /// 已发送但未确认的字节数
+    pub fn unacked(&self) -> usize {
+        self.state.lock().unwrap().unacked
+    }
+
+    /// 是否暂停发送（未确认字节数达到高水位时进入暂停，确认到低水位以下才解除）
+    pub fn is_paused(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if self.enabled && state.acked && state.unacked >= self.high_watermark {
+            state.paused = true;
+        }
+        state.paused
+    }
+
+    /// 等待暂停解除（会话关闭时也返回）
+    pub async fn resumed(&self) {
+        loop {
+            let notified = self.notify.notified();
+            {
+                let state = self.state.lock().unwrap();
+                if !state.paused || state.closed {
+                    return;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// 会话是否已关闭
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    /// 重新附加的窗口接管输出时清零并解除暂停（旧窗口的确认已丢失）
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.unacked = 0;
+        state.paused = false;
+        self.notify.notify_waiters();
+    }
+
+    /// 会话关闭时唤醒等待中的读取任务
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.notify.notify_waiters();
+    }
+}
+
+/// 通过 ssh-data-{id} 事件发送一批输出 { offset, data }（data 为 Base64），并写入回滚缓冲区
+/// - offset 为这批输出在会话输出中的起始偏移量，重新附加的窗口据此去掉与回滚内容重叠的部分
+pub fn emit(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    flow: &FlowControl,
+    scrollback: &Scrollback,
+    data: &[u8],
+) {
+    if data.is_empty() {
+        return;
+    }
+
+    flow.sent(data.len());
+    let offset = scrollback.push(data);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    let _ = app_handle.emit_all(
+        &format!("ssh-data-{}", session_id),
+        json!({ "offset": offset, "data": encoded }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(flush_interval_ms: u64, flush_bytes: usize) -> OutputOptions {
+        OutputOptions {
+            flush_interval_ms,
+            flush_bytes,
+            high_watermark: 100,
+            low_watermark: 40,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_batcher_flushes_on_size() {
+        let mut batcher = OutputBatcher::new(&options(50, 8));
+        assert!(batcher.deadline().is_none());
+        assert!(!batcher.push(b"abc"));
+        assert!(batcher.deadline().is_some());
+        assert!(batcher.push(b"defgh"));
+        assert_eq!(batcher.take(), b"abcdefgh");
+        assert!(batcher.deadline().is_none());
+    }
+
+    #[test]
+    fn test_batcher_zero_interval_sends_immediately() {
+        let mut batcher = OutputBatcher::new(&options(0, 1024));
+        assert!(batcher.push(b"x"));
+    }
+
+    #[test]
+    fn test_flow_control_requires_ack() {
+        let flow = FlowControl::new(&options(16, 1024));
+        flow.sent(500);
+        // 从未确认过的前端不受流控影响
+        assert!(!flow.is_paused());
+
+        flow.ack(100);
+        assert_eq!(flow.unacked(), 400);
+        assert!(flow.is_paused());
+
+        // 降到高水位以下但仍高于低水位时保持暂停
+        flow.ack(320);
+        assert!(flow.is_paused());
+
+        flow.ack(40);
+        assert!(!flow.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_resumed_after_ack() {
+        let flow = std::sync::Arc::new(FlowControl::new(&options(16, 1024)));
+        flow.ack(0);
+        flow.sent(200);
+        assert!(flow.is_paused());
+
+        let waiter = tokio::spawn({
+            let flow = flow.clone();
+            async move { flow.resumed().await }
+        });
+        tokio::task::yield_now().await;
+        flow.ack(100);
+        assert!(!waiter.is_finished());
+        flow.ack(100);
+        waiter.await.unwrap();
+        assert!(!flow.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_stays_paused_without_ack() {
+        let flow = FlowControl::new(&options(16, 1024));
+        flow.ack(0);
+        flow.sent(200);
+        assert!(flow.is_paused());
+
+        // 前端不再确认时不会自行恢复
+        let resumed = tokio::time::timeout(Duration::from_millis(50), flow.resumed()).await;
+        assert!(resumed.is_err());
+
+        flow.close();
+        flow.resumed().await;
+        assert!(flow.is_closed());
+    }
+
+    #[test]
+    fn test_reset_resumes() {
+        let flow = FlowControl::new(&options(16, 1024));
+        flow.ack(0);
+        flow.sent(200);
+        assert!(flow.is_paused());
+
+        // 重新附加的窗口接管输出时解除暂停
+        flow.reset();
+        assert!(!flow.is_paused());
+        assert_eq!(flow.unacked(), 0);
+    }
+}
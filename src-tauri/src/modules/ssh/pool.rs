@@ -0,0 +1,735 @@
+use super::client::{authenticate, connect, load_ssh_config, SSHClientHandler, SessionOptions};
+use super::jump::JumpChain;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use russh::client::Handle;
+use russh::Disconnect;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify, RwLock};
+
+/// 新建立的已认证连接
+pub struct Connected<H> {
+    pub handle: H,
+    pub jump_chain: Option<JumpChain>,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    /// 是否接受服务器的 agent 转发 channel（与连接的 Handler 共享）
+    pub agent_forwarding: Arc<AtomicBool>,
+}
+
+/// 为连接池建立、检查和断开连接
+/// - 连接池只负责计数和重连，具体的连接由 Connector 完成（测试中替换为不需要服务器的实现）
+#[async_trait]
+pub trait Connector: Send + Sync + 'static {
+    type Handle: Send + Sync + 'static;
+
+    /// 建立连接并认证
+    async fn connect(
+        &self,
+        connection_id: &str,
+        options: &SessionOptions,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Result<Connected<Self::Handle>>;
+
+    /// 连接是否已断开
+    fn is_closed(&self, handle: &Self::Handle) -> bool;
+
+    /// 断开连接
+    async fn disconnect(&self, handle: &Self::Handle);
+}
+
+/// 使用已保存连接的配置建立 SSH 连接
+pub struct SshConnector;
+
+#[async_trait]
+impl Connector for SshConnector {
+    type Handle = Handle<SSHClientHandler>;
+
+    async fn connect(
+        &self,
+        connection_id: &str,
+        options: &SessionOptions,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Result<Connected<Self::Handle>> {
+        // 每次连接都重新读取配置，连接信息修改后重连即可生效
+        let ssh_config = load_ssh_config(connection_id).await?;
+        let handler = SSHClientHandler::new(
+            connection_id,
+            &ssh_config.host,
+            ssh_config.port,
+            app_handle.clone(),
+        )
+        .with_agent_forwarding(options.agent_forwarding)
+        .pooled();
+        let agent_forwarding = handler.agent_forwarding();
+
+        let (mut handle, jump_chain) = connect(
+            Arc::new(options.client_config()),
+            &ssh_config.host,
+            ssh_config.port,
+            ssh_config.jump_host.as_ref(),
+            handler,
+            connection_id,
+            app_handle.clone(),
+        )
+        .await?;
+
+        authenticate(
+            &mut handle,
+            &ssh_config.username,
+            ssh_config.auth_method.as_str(),
+            ssh_config.password.as_deref(),
+            ssh_config.private_key_path.as_deref(),
+            ssh_config.passphrase.as_deref(),
+            ssh_config.certificate_path.as_deref(),
+            connection_id,
+            app_handle.as_ref(),
+        )
+        .await?;
+
+        Ok(Connected {
+            handle,
+            jump_chain,
+            host: ssh_config.host,
+            port: ssh_config.port,
+            username: ssh_config.username,
+            agent_forwarding,
+        })
+    }
+
+    fn is_closed(&self, handle: &Self::Handle) -> bool {
+        handle.is_closed()
+    }
+
+    async fn disconnect(&self, handle: &Self::Handle) {
+        let _ = handle
+            .disconnect(Disconnect::ByApplication, "", "English")
+            .await;
+    }
+}
+
+/// 连接池中的已认证连接
+struct PooledConnection<H> {
+    /// 共享的连接 Handle（见 SharedHandle），重连时只替换其中的内容，持有者无需更新引用
+    transport: Arc<RwLock<H>>,
+    jump_chain: Option<JumpChain>,
+    /// 每次（重新）建立连接时加一，用于判断 channel 所在的连接是否已被替换
+    generation: u64,
+    /// 建立连接时使用的选项，重连时沿用
+    options: SessionOptions,
+    agent_forwarding: Arc<AtomicBool>,
+    host: String,
+    port: u16,
+    username: String,
+    connected_at: String,
+}
+
+impl<H> PooledConnection<H> {
+    /// 检查已有连接能否满足使用者请求的选项
+    /// - keepalive 作用于整个连接，与已有连接不同时返回错误
+    /// - agent 转发在连接建立后开启，之后的重连也会开启
+    fn accept_options(&mut self, connection_id: &str, requested: &SessionOptions) -> Result<()> {
+        if requested.keepalive_interval != self.options.keepalive_interval
+            || requested.keepalive_max != self.options.keepalive_max
+        {
+            return Err(anyhow!(
+                "Connection {} is already open with keepalive interval {}s and max {}; \
+                 close its other sessions to change keepalive settings",
+                connection_id,
+                self.options.keepalive_interval,
+                self.options.keepalive_max
+            ));
+        }
+        if requested.agent_forwarding && !self.options.agent_forwarding {
+            self.options.agent_forwarding = true;
+            self.agent_forwarding.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+struct Slot<H> {
+    connection: Option<PooledConnection<H>>,
+    users: usize,
+    /// 最后一个使用者已释放，正在从连接池中移除
+    closed: bool,
+}
+
+type SlotRef<H> = Arc<Mutex<Slot<H>>>;
+
+impl<H> Default for Slot<H> {
+    fn default() -> Self {
+        Self {
+            connection: None,
+            users: 0,
+            closed: false,
+        }
+    }
+}
+
+/// 连接池中的连接信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct PooledConnectionInfo {
+    pub connection_id: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    /// 正在使用该连接的终端、SFTP、exec 数量
+    pub users: usize,
+    pub connected_at: String,
+    /// 自动重连的次数
+    pub reconnects: u64,
+}
+
+/// 已保存连接的连接池
+/// - 每个连接只建立一次 TCP 连接并认证一次，终端标签页、SFTP、exec 在同一连接上打开各自的 channel
+/// - 按使用者计数，最后一个使用者释放时断开连接
+pub struct ConnectionPool<C: Connector = SshConnector> {
+    slots: Mutex<HashMap<String, SlotRef<C::Handle>>>,
+    /// 有位置从连接池中移除时通知（等待被关闭的位置移除后重新创建）
+    removed: Notify,
+    connector: C,
+}
+
+/// 连接池中连接的使用权，释放后计数减一
+pub struct ConnectionLease<C: Connector = SshConnector> {
+    pool: &'static ConnectionPool<C>,
+    connection_id: String,
+    transport: Arc<RwLock<C::Handle>>,
+    generation: u64,
+    released: bool,
+}
+
+impl<C: Connector> ConnectionLease<C> {
+    pub fn connection_id(&self) -> &str {
+        &self.connection_id
+    }
+
+    /// 共享的连接 Handle
+    pub fn transport(&self) -> Arc<RwLock<C::Handle>> {
+        self.transport.clone()
+    }
+
+    /// 获取使用权时连接的代数
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 释放使用权
+    pub async fn release(mut self) {
+        self.released = true;
+        self.pool.release(&self.connection_id).await;
+    }
+}
+
+impl<C: Connector> Drop for ConnectionLease<C> {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        // 未显式释放时在后台释放
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let pool = self.pool;
+            let connection_id = self.connection_id.clone();
+            runtime.spawn(async move {
+                pool.release(&connection_id).await;
+            });
+        }
+    }
+}
+
+impl ConnectionPool {
+    pub fn new() -> Self {
+        Self::with_connector(SshConnector)
+    }
+}
+
+impl<C: Connector> ConnectionPool<C> {
+    pub fn with_connector(connector: C) -> Self {
+        Self {
+            slots: Mutex::new(HashMap::new()),
+            removed: Notify::new(),
+            connector,
+        }
+    }
+
+    /// 获取已保存连接的使用权，连接不存在或已断开时建立连接并认证
+    /// - 同一连接的并发请求会等待第一次认证完成后共用该连接
+    /// - options 为 None 时（SFTP、exec 等不关心连接选项的使用者）沿用已有连接的选项，
+    ///   新建连接时使用默认选项；指定 options 时需与已有连接兼容（见 accept_options）
+    pub async fn acquire(
+        &'static self,
+        connection_id: &str,
+        options: Option<&SessionOptions>,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Result<ConnectionLease<C>> {
+        loop {
+            let slot_ref = self
+                .slots
+                .lock()
+                .await
+                .entry(connection_id.to_string())
+                .or_default()
+                .clone();
+
+            let mut slot = slot_ref.lock().await;
+            if slot.closed {
+                // 正在被移除，等待移除完成后重新创建
+                let removed = self.removed.notified();
+                tokio::pin!(removed);
+                removed.as_mut().enable();
+                drop(slot);
+                if self.contains_slot(connection_id, &slot_ref).await {
+                    removed.await;
+                }
+                continue;
+            }
+
+            let (transport, generation) = match self
+                .ensure_connected(connection_id, &mut slot, options, app_handle)
+                .await
+            {
+                Ok(connected) => connected,
+                Err(e) => {
+                    // 首次连接失败时不在池中留下空位
+                    if slot.users == 0 {
+                        slot.closed = true;
+                        drop(slot);
+                        self.remove_slot(connection_id, &slot_ref).await;
+                    }
+                    return Err(e);
+                }
+            };
+            slot.users += 1;
+
+            return Ok(ConnectionLease {
+                pool: self,
+                connection_id: connection_id.to_string(),
+                transport,
+                generation,
+                released: false,
+            });
+        }
+    }
+
+    /// 连接已断开时重新连接（不增加使用者计数），返回连接 Handle 和当前代数
+    /// - 多个终端同时检测到断开时只会重连一次
+    /// - 使用建立连接时的选项重连
+    pub async fn reconnect(
+        &self,
+        connection_id: &str,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Result<(Arc<RwLock<C::Handle>>, u64)> {
+        let slot = self
+            .slots
+            .lock()
+            .await
+            .get(connection_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Connection is not open: {}", connection_id))?;
+
+        let mut slot = slot.lock().await;
+        if slot.closed || slot.users == 0 {
+            return Err(anyhow!("Connection is not open: {}", connection_id));
+        }
+        self.ensure_connected(connection_id, &mut slot, None, app_handle)
+            .await
+    }
+
+    /// 当前连接的代数（连接不在池中时为 None）
+    pub async fn generation(&self, connection_id: &str) -> Option<u64> {
+        let slot = self.slots.lock().await.get(connection_id).cloned()?;
+        let slot = slot.lock().await;
+        slot.connection.as_ref().map(|c| c.generation)
+    }
+
+    async fn ensure_connected(
+        &self,
+        connection_id: &str,
+        slot: &mut Slot<C::Handle>,
+        requested: Option<&SessionOptions>,
+        app_handle: Option<tauri::AppHandle>,
+    ) -> Result<(Arc<RwLock<C::Handle>>, u64)> {
+        if let Some(connection) = &mut slot.connection {
+            if let Some(requested) = requested {
+                connection.accept_options(connection_id, requested)?;
+            }
+            if !self
+                .connector
+                .is_closed(&*connection.transport.read().await)
+            {
+                return Ok((connection.transport.clone(), connection.generation));
+            }
+        }
+
+        let options = match (&slot.connection, requested) {
+            (Some(connection), _) => connection.options.clone(),
+            (None, Some(requested)) => requested.clone(),
+            (None, None) => SessionOptions::default(),
+        };
+        let connected = self
+            .connector
+            .connect(connection_id, &options, app_handle)
+            .await?;
+
+        let connected_at = Utc::now().to_rfc3339();
+        match &mut slot.connection {
+            Some(connection) => {
+                *connection.transport.write().await = connected.handle;
+                let old_jump_chain =
+                    std::mem::replace(&mut connection.jump_chain, connected.jump_chain);
+                if let Some(old_jump_chain) = old_jump_chain {
+                    old_jump_chain.close().await;
+                }
+                connection.generation += 1;
+                connection.agent_forwarding = connected.agent_forwarding;
+                connection.host = connected.host;
+                connection.port = connected.port;
+                connection.username = connected.username;
+                connection.connected_at = connected_at;
+                Ok((connection.transport.clone(), connection.generation))
+            }
+            None => {
+                let transport = Arc::new(RwLock::new(connected.handle));
+                slot.connection = Some(PooledConnection {
+                    transport: transport.clone(),
+                    jump_chain: connected.jump_chain,
+                    generation: 0,
+                    options,
+                    agent_forwarding: connected.agent_forwarding,
+                    host: connected.host,
+                    port: connected.port,
+                    username: connected.username,
+                    connected_at,
+                });
+                Ok((transport, 0))
+            }
+        }
+    }
+
+    /// 使用者计数减一，归零时断开连接
+    async fn release(&self, connection_id: &str) {
+        let Some(slot_ref) = self.slots.lock().await.get(connection_id).cloned() else {
+            return;
+        };
+
+        let connection = {
+            let mut slot = slot_ref.lock().await;
+            slot.users = slot.users.saturating_sub(1);
+            if slot.users > 0 {
+                return;
+            }
+            slot.closed = true;
+            slot.connection.take()
+        };
+
+        self.remove_slot(connection_id, &slot_ref).await;
+
+        if let Some(connection) = connection {
+            self.connector
+                .disconnect(&*connection.transport.read().await)
+                .await;
+            if let Some(jump_chain) = connection.jump_chain {
+                jump_chain.close().await;
+            }
+        }
+    }
+
+    /// 该位置是否仍在连接池中
+    async fn contains_slot(&self, connection_id: &str, slot_ref: &SlotRef<C::Handle>) -> bool {
+        self.slots
+            .lock()
+            .await
+            .get(connection_id)
+            .is_some_and(|slot| Arc::ptr_eq(slot, slot_ref))
+    }
+
+    /// 从连接池中移除（仅当仍是同一个位置时），并唤醒等待该位置移除的请求
+    async fn remove_slot(&self, connection_id: &str, slot_ref: &SlotRef<C::Handle>) {
+        let mut slots = self.slots.lock().await;
+        if slots
+            .get(connection_id)
+            .is_some_and(|slot| Arc::ptr_eq(slot, slot_ref))
+        {
+            slots.remove(connection_id);
+        }
+        drop(slots);
+        self.removed.notify_waiters();
+    }
+
+    /// 列出连接池中的连接
+    pub async fn list(&self) -> Vec<PooledConnectionInfo> {
+        let slots: Vec<(String, SlotRef<C::Handle>)> = self
+            .slots
+            .lock()
+            .await
+            .iter()
+            .map(|(id, slot)| (id.clone(), slot.clone()))
+            .collect();
+
+        let mut infos = Vec::with_capacity(slots.len());
+        for (connection_id, slot) in slots {
+            // 正在认证的连接跳过，避免等待用户输入
+            let Ok(slot) = slot.try_lock() else {
+                continue;
+            };
+            if let Some(connection) = &slot.connection {
+                infos.push(PooledConnectionInfo {
+                    connection_id,
+                    host: connection.host.clone(),
+                    port: connection.port,
+                    username: connection.username.clone(),
+                    users: slot.users,
+                    connected_at: connection.connected_at.clone(),
+                    reconnects: connection.generation,
+                });
+            }
+        }
+        infos.sort_by(|a, b| a.connection_id.cmp(&b.connection_id));
+        infos
+    }
+}
+
+/// 全局连接池
+static CONNECTION_POOL: Lazy<ConnectionPool> = Lazy::new(ConnectionPool::new);
+
+pub fn get_connection_pool() -> &'static ConnectionPool {
+    &CONNECTION_POOL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// 不需要服务器的连接：closed 标记连接已断开
+    struct MockHandle {
+        closed: bool,
+    }
+
+    #[derive(Default)]
+    struct MockConnector {
+        connects: AtomicUsize,
+        disconnects: AtomicUsize,
+        fail: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Connector for MockConnector {
+        type Handle = MockHandle;
+
+        async fn connect(
+            &self,
+            connection_id: &str,
+            options: &SessionOptions,
+            _app_handle: Option<tauri::AppHandle>,
+        ) -> Result<Connected<MockHandle>> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(anyhow!("Authentication failed"));
+            }
+            self.connects.fetch_add(1, Ordering::SeqCst);
+            Ok(Connected {
+                handle: MockHandle { closed: false },
+                jump_chain: None,
+                host: format!("{}.example.com", connection_id),
+                port: 22,
+                username: "deploy".to_string(),
+                agent_forwarding: Arc::new(AtomicBool::new(options.agent_forwarding)),
+            })
+        }
+
+        fn is_closed(&self, handle: &MockHandle) -> bool {
+            handle.closed
+        }
+
+        async fn disconnect(&self, _handle: &MockHandle) {
+            self.disconnects.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn mock_pool() -> &'static ConnectionPool<MockConnector> {
+        Box::leak(Box::new(ConnectionPool::with_connector(
+            MockConnector::default(),
+        )))
+    }
+
+    async fn acquire(
+        pool: &'static ConnectionPool<MockConnector>,
+    ) -> Result<ConnectionLease<MockConnector>> {
+        pool.acquire("web", Some(&SessionOptions::default()), None)
+            .await
+    }
+
+    /// 连接当前是否接受 agent 转发 channel
+    async fn agent_forwarding(pool: &ConnectionPool<MockConnector>) -> bool {
+        let slot = pool.slots.lock().await.get("web").cloned().unwrap();
+        let slot = slot.lock().await;
+        let connection = slot.connection.as_ref().unwrap();
+        connection.agent_forwarding.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_acquire_shares_connection_and_counts_users() {
+        let pool = mock_pool();
+        let first = acquire(pool).await.unwrap();
+        let second = acquire(pool).await.unwrap();
+
+        assert!(Arc::ptr_eq(&first.transport(), &second.transport()));
+        assert_eq!(pool.connector.connects.load(Ordering::SeqCst), 1);
+        let infos = pool.list().await;
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].users, 2);
+        assert_eq!(infos[0].host, "web.example.com");
+
+        first.release().await;
+        assert_eq!(pool.list().await[0].users, 1);
+        assert_eq!(pool.connector.disconnects.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_last_release_disconnects_and_removes_slot() {
+        let pool = mock_pool();
+        let first = acquire(pool).await.unwrap();
+        let second = acquire(pool).await.unwrap();
+
+        first.release().await;
+        second.release().await;
+        assert_eq!(pool.connector.disconnects.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.generation("web").await, None);
+        assert!(pool.list().await.is_empty());
+
+        // 再次获取时重新建立连接
+        let lease = acquire(pool).await.unwrap();
+        assert_eq!(lease.generation(), 0);
+        assert_eq!(pool.connector.connects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_lease_is_released() {
+        let pool = mock_pool();
+        drop(acquire(pool).await.unwrap());
+
+        for _ in 0..100 {
+            if pool.generation("web").await.is_none() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(pool.generation("web").await, None);
+        assert_eq!(pool.connector.disconnects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_first_connect_leaves_no_slot() {
+        let pool = mock_pool();
+        pool.connector.fail.store(true, Ordering::SeqCst);
+
+        assert!(acquire(pool).await.is_err());
+        assert!(pool.slots.lock().await.is_empty());
+
+        pool.connector.fail.store(false, Ordering::SeqCst);
+        assert!(acquire(pool).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_bumps_generation() {
+        let pool = mock_pool();
+        let lease = acquire(pool).await.unwrap();
+
+        // 连接正常时不重连
+        let (_, generation) = pool.reconnect("web", None).await.unwrap();
+        assert_eq!(generation, 0);
+        assert_eq!(pool.connector.connects.load(Ordering::SeqCst), 1);
+
+        lease.transport().write().await.closed = true;
+        let (transport, generation) = pool.reconnect("web", None).await.unwrap();
+        assert_eq!(generation, 1);
+        assert_eq!(pool.generation("web").await, Some(1));
+        assert!(Arc::ptr_eq(&transport, &lease.transport()));
+        assert!(!transport.read().await.closed);
+        assert_eq!(pool.list().await[0].reconnects, 1);
+
+        // 重连失败时保留原有位置和计数
+        transport.write().await.closed = true;
+        pool.connector.fail.store(true, Ordering::SeqCst);
+        assert!(pool.reconnect("web", None).await.is_err());
+        assert_eq!(pool.generation("web").await, Some(1));
+
+        lease.release().await;
+        assert!(pool.reconnect("web", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_rejects_different_keepalive() {
+        let pool = mock_pool();
+        let lease = acquire(pool).await.unwrap();
+
+        let options = SessionOptions {
+            keepalive_interval: 60,
+            ..Default::default()
+        };
+        assert!(pool.acquire("web", Some(&options), None).await.is_err());
+        assert_eq!(pool.list().await[0].users, 1);
+
+        // 不指定选项的使用者沿用已有连接
+        let any = pool.acquire("web", None, None).await.unwrap();
+        assert_eq!(pool.connector.connects.load(Ordering::SeqCst), 1);
+        any.release().await;
+        lease.release().await;
+    }
+
+    #[tokio::test]
+    async fn test_acquire_enables_agent_forwarding_on_shared_connection() {
+        let pool = mock_pool();
+        let lease = pool.acquire("web", None, None).await.unwrap();
+        assert!(!agent_forwarding(pool).await);
+
+        let options = SessionOptions {
+            agent_forwarding: true,
+            ..Default::default()
+        };
+        let forwarding = pool.acquire("web", Some(&options), None).await.unwrap();
+        assert!(agent_forwarding(pool).await);
+        assert_eq!(pool.connector.connects.load(Ordering::SeqCst), 1);
+
+        // 重连后的连接仍然开启
+        lease.transport().write().await.closed = true;
+        pool.reconnect("web", None).await.unwrap();
+        assert!(agent_forwarding(pool).await);
+
+        forwarding.release().await;
+        lease.release().await;
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_closed_slot_removal() {
+        let pool = mock_pool();
+        let closed = Arc::new(Mutex::new(Slot {
+            connection: None,
+            users: 0,
+            closed: true,
+        }));
+        pool.slots
+            .lock()
+            .await
+            .insert("web".to_string(), closed.clone());
+
+        let waiting = tokio::spawn(acquire(pool));
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        pool.remove_slot("web", &closed).await;
+        let lease = tokio::time::timeout(std::time::Duration::from_secs(1), waiting)
+            .await
+            .expect("acquire did not wake up")
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.generation(), 0);
+    }
+}
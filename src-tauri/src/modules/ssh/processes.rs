@@ -0,0 +1,513 @@
+use super::client::get_ssh_manager;
+use super::exec::run_command_with_input;
+use anyhow::{anyhow, Result};
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// ps / kill 的超时时间
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 列出进程的命令
+/// - 第一行为远程主机的当前时间（用于根据 etime 计算启动时间）
+/// - 不支持 -A / pcpu 的 ps（如 BusyBox）退回到带表头的精简格式
+const PS_COMMAND: &str = "date +%s; \
+     ps -A -o pid= -o ppid= -o user= -o pcpu= -o pmem= -o rss= -o etime= -o args= 2>/dev/null \
+     || { echo @@minimal; ps -o pid,ppid,user,rss,etime,args; }";
+
+/// 从结果中排除 ps 自身
+const PS_SELF_MARKER: &str = "-o pid= -o ppid=";
+
+/// 远程进程
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    pub user: String,
+    /// CPU 使用率（精简格式下为 None）
+    pub cpu_percent: Option<f64>,
+    /// 内存使用率（精简格式下为 None）
+    pub mem_percent: Option<f64>,
+    /// 常驻内存（字节）
+    pub rss: Option<u64>,
+    /// 已运行的秒数
+    pub elapsed_secs: Option<u64>,
+    /// 启动时间（按远程主机时间计算）
+    pub started_at: Option<String>,
+    /// 完整命令行
+    pub command: String,
+}
+
+/// 排序字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessSort {
+    Pid,
+    User,
+    #[default]
+    Cpu,
+    Memory,
+    /// 按启动时间
+    Started,
+    Command,
+}
+
+/// 进程列表的过滤与排序条件
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProcessQuery {
+    /// 按 PID、用户或命令行过滤（忽略大小写的子串匹配）
+    pub filter: Option<String>,
+    /// 只返回该用户的进程
+    pub user: Option<String>,
+    pub sort_by: ProcessSort,
+    pub descending: bool,
+    /// 最多返回的进程数
+    pub limit: Option<usize>,
+}
+
+impl Default for ProcessQuery {
+    fn default() -> Self {
+        Self {
+            filter: None,
+            user: None,
+            sort_by: ProcessSort::Cpu,
+            descending: true,
+            limit: None,
+        }
+    }
+}
+
+/// 进程列表
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessList {
+    pub processes: Vec<ProcessInfo>,
+    /// 过滤前的进程总数
+    pub total: usize,
+}
+
+/// 可发送的信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ProcessSignal {
+    Term,
+    Kill,
+    Hup,
+}
+
+impl ProcessSignal {
+    fn name(&self) -> &'static str {
+        match self {
+            ProcessSignal::Term => "TERM",
+            ProcessSignal::Kill => "KILL",
+            ProcessSignal::Hup => "HUP",
+        }
+    }
+}
+
+/// 使用 sudo 发送信号
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SudoOptions {
+    /// sudo 密码，不提供时以非交互方式运行（需要免密 sudo）
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// 单个进程的信号发送结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalResult {
+    pub pid: u32,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 列出会话所在主机的进程，按条件过滤和排序
+pub async fn list(session_id: &str, query: &ProcessQuery) -> Result<ProcessList> {
+    let transport = get_ssh_manager().get_transport(session_id).await?;
+    let output = run_command_with_input(
+        &transport,
+        PS_COMMAND,
+        None,
+        Some(COMMAND_TIMEOUT),
+        None,
+        |_, _| {},
+    )
+    .await?;
+
+    if output.timed_out {
+        return Err(anyhow!("Timed out listing processes"));
+    }
+    if output.exit_status != Some(0) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to run ps: {}", stderr.trim()));
+    }
+
+    let processes = parse_ps(&String::from_utf8_lossy(&output.stdout));
+    Ok(apply_query(processes, query))
+}
+
+/// 向进程发送信号，返回每个进程的结果
+pub async fn signal(
+    session_id: &str,
+    pids: &[u32],
+    signal: ProcessSignal,
+    sudo: Option<&SudoOptions>,
+) -> Result<Vec<SignalResult>> {
+    if pids.is_empty() {
+        return Ok(Vec::new());
+    }
+    if pids.contains(&0) {
+        return Err(anyhow!("Invalid pid: 0"));
+    }
+
+    let (command, input) = signal_command(pids, signal, sudo);
+    let transport = get_ssh_manager().get_transport(session_id).await?;
+    let output = run_command_with_input(
+        &transport,
+        &command,
+        input.as_deref().map(str::as_bytes),
+        Some(COMMAND_TIMEOUT),
+        None,
+        |_, _| {},
+    )
+    .await?;
+
+    if output.timed_out {
+        return Err(anyhow!("Timed out sending signal"));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() && output.exit_status != Some(0) {
+        // sudo 认证失败等情况下脚本没有执行
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to send signal: {}", stderr.trim()));
+    }
+    Ok(parse_signal_output(&stdout, pids))
+}
+
+/// 构造发送信号的命令，返回 (命令, 标准输入)
+/// - 用 sh -c 执行，不依赖用户的登录 shell；每个 pid 单独 kill 以便分别返回结果
+fn signal_command(
+    pids: &[u32],
+    signal: ProcessSignal,
+    sudo: Option<&SudoOptions>,
+) -> (String, Option<String>) {
+    let pids = pids
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let script = format!(
+        "for pid in {}; do if err=$(kill -{} $pid 2>&1); then echo \"$pid ok\"; else echo \"$pid $err\"; fi; done",
+        pids,
+        signal.name()
+    );
+
+    match sudo {
+        None => (format!("sh -c '{}'", script), None),
+        Some(SudoOptions {
+            password: Some(password),
+        }) => (
+            format!("sudo -S -p '' sh -c '{}'", script),
+            Some(format!("{}\n", password)),
+        ),
+        Some(SudoOptions { password: None }) => (format!("sudo -n sh -c '{}'", script), None),
+    }
+}
+
+/// 解析信号脚本的输出（每行 "<pid> ok" 或 "<pid> <错误信息>"）
+fn parse_signal_output(output: &str, pids: &[u32]) -> Vec<SignalResult> {
+    pids.iter()
+        .map(|&pid| {
+            let line = output.lines().find_map(|line| {
+                let (line_pid, rest) = line.split_once(' ')?;
+                (line_pid.parse::<u32>().ok()? == pid).then_some(rest.trim())
+            });
+            match line {
+                Some("ok") => SignalResult {
+                    pid,
+                    success: true,
+                    error: None,
+                },
+                Some(error) => SignalResult {
+                    pid,
+                    success: false,
+                    error: Some(error.to_string()),
+                },
+                None => SignalResult {
+                    pid,
+                    success: false,
+                    error: Some("No result".to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// 解析 PS_COMMAND 的输出
+fn parse_ps(output: &str) -> Vec<ProcessInfo> {
+    let mut lines = output.lines();
+    let now = lines
+        .next()
+        .and_then(|line| line.trim().parse::<i64>().ok());
+
+    let mut minimal = false;
+    let mut processes = Vec::new();
+    for line in lines {
+        if line.trim() == "@@minimal" {
+            minimal = true;
+            processes.clear();
+            continue;
+        }
+
+        let process = if minimal {
+            parse_minimal_line(line)
+        } else {
+            parse_full_line(line)
+        };
+        let Some(mut process) = process else {
+            continue;
+        };
+        if process.command.contains(PS_SELF_MARKER) {
+            continue;
+        }
+
+        process.started_at = now
+            .zip(process.elapsed_secs)
+            .and_then(|(now, elapsed)| Utc.timestamp_opt(now - elapsed as i64, 0).single())
+            .map(|started| started.to_rfc3339());
+        processes.push(process);
+    }
+    processes
+}
+
+/// pid ppid user pcpu pmem rss etime args
+fn parse_full_line(line: &str) -> Option<ProcessInfo> {
+    let (fields, command) = split_fields(line, 7)?;
+    Some(ProcessInfo {
+        pid: fields[0].parse().ok()?,
+        ppid: fields[1].parse().ok()?,
+        user: fields[2].to_string(),
+        cpu_percent: fields[3].parse().ok(),
+        mem_percent: fields[4].parse().ok(),
+        rss: parse_kb(fields[5]),
+        elapsed_secs: parse_etime(fields[6]),
+        started_at: None,
+        command: command.to_string(),
+    })
+}
+
+/// PID PPID USER RSS ELAPSED COMMAND（带表头）
+fn parse_minimal_line(line: &str) -> Option<ProcessInfo> {
+    let (fields, command) = split_fields(line, 5)?;
+    Some(ProcessInfo {
+        pid: fields[0].parse().ok()?,
+        ppid: fields[1].parse().ok()?,
+        user: fields[2].to_string(),
+        cpu_percent: None,
+        mem_percent: None,
+        rss: parse_kb(fields[3]),
+        elapsed_secs: parse_etime(fields[4]),
+        started_at: None,
+        command: command.to_string(),
+    })
+}
+
+/// 取出前 n 个以空白分隔的字段，剩余部分（命令行）原样返回
+fn split_fields(line: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = line.trim_start();
+    for _ in 0..n {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    Some((fields, rest.trim_end()))
+}
+
+/// 解析以 KB 为单位的大小（BusyBox 可能带 m/g 后缀）
+fn parse_kb(value: &str) -> Option<u64> {
+    let (number, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1),
+        'm' | 'M' => (&value[..value.len() - 1], 1024),
+        'g' | 'G' => (&value[..value.len() - 1], 1024 * 1024),
+        _ => (value, 1),
+    };
+    let kb = number.parse::<f64>().ok()? * multiplier as f64;
+    Some((kb * 1024.0) as u64)
+}
+
+/// 解析 etime（[[dd-]hh:]mm:ss）
+fn parse_etime(value: &str) -> Option<u64> {
+    let (days, clock) = match value.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, value),
+    };
+
+    let parts = clock
+        .split(':')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        [minutes, seconds] => (0, minutes, seconds),
+        _ => return None,
+    };
+
+    Some(((days * 24 + hours) * 60 + minutes) * 60 + seconds)
+}
+
+/// 按条件过滤、排序并截断
+fn apply_query(processes: Vec<ProcessInfo>, query: &ProcessQuery) -> ProcessList {
+    let total = processes.len();
+    let filter = query
+        .filter
+        .as_deref()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(str::to_lowercase);
+
+    let mut processes: Vec<ProcessInfo> = processes
+        .into_iter()
+        .filter(|p| query.user.is_none() || query.user.as_deref() == Some(p.user.as_str()))
+        .filter(|p| match &filter {
+            Some(filter) => {
+                p.pid.to_string() == *filter
+                    || p.user.to_lowercase().contains(filter)
+                    || p.command.to_lowercase().contains(filter)
+            }
+            None => true,
+        })
+        .collect();
+
+    processes.sort_by(|a, b| {
+        let ordering = match query.sort_by {
+            ProcessSort::Pid => a.pid.cmp(&b.pid),
+            ProcessSort::User => a.user.cmp(&b.user),
+            ProcessSort::Cpu => compare_f64(a.cpu_percent, b.cpu_percent),
+            ProcessSort::Memory => {
+                compare_f64(a.mem_percent, b.mem_percent).then_with(|| a.rss.cmp(&b.rss))
+            }
+            // 运行时间越长启动越早
+            ProcessSort::Started => b.elapsed_secs.cmp(&a.elapsed_secs),
+            ProcessSort::Command => a.command.cmp(&b.command),
+        };
+        let ordering = if query.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        ordering.then_with(|| a.pid.cmp(&b.pid))
+    });
+
+    if let Some(limit) = query.limit {
+        processes.truncate(limit);
+    }
+
+    ProcessList { processes, total }
+}
+
+fn compare_f64(a: Option<f64>, b: Option<f64>) -> Ordering {
+    a.unwrap_or(0.0).total_cmp(&b.unwrap_or(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PS_OUTPUT: &str = "1700000000
+    1     0 root      0.0  0.1  11800        2-03:04:05 /sbin/init splash
+  812     1 www-data 12.5  3.2 204800             01:30 nginx: worker process
+  900   812 alice     0.0  0.0   3200          01:00:00 bash
+  950   900 alice    50.0  1.0  10240             00:05 ps -A -o pid= -o ppid= -o user= -o pcpu= -o pmem= -o rss= -o etime= -o args=
+";
+
+    #[test]
+    fn test_parse_ps() {
+        let processes = parse_ps(PS_OUTPUT);
+        assert_eq!(processes.len(), 3);
+
+        let init = &processes[0];
+        assert_eq!(init.pid, 1);
+        assert_eq!(init.command, "/sbin/init splash");
+        assert_eq!(init.elapsed_secs, Some(((2 * 24 + 3) * 60 + 4) * 60 + 5));
+        assert_eq!(init.rss, Some(11800 * 1024));
+
+        let nginx = &processes[1];
+        assert_eq!(nginx.user, "www-data");
+        assert_eq!(nginx.cpu_percent, Some(12.5));
+        assert_eq!(nginx.command, "nginx: worker process");
+        assert_eq!(
+            nginx.started_at.as_deref(),
+            Some("2023-11-14T22:11:50+00:00")
+        );
+    }
+
+    #[test]
+    fn test_parse_minimal_ps() {
+        let output = "1700000000\n@@minimal\nPID   PPID  USER     RSS  ELAPSED COMMAND\n    1     0 root     1.2m   10:00 init\n";
+        let processes = parse_ps(output);
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].cpu_percent, None);
+        assert_eq!(processes[0].rss, Some((1.2 * 1024.0 * 1024.0) as u64));
+        assert_eq!(processes[0].elapsed_secs, Some(600));
+    }
+
+    #[test]
+    fn test_apply_query() {
+        let processes = parse_ps(PS_OUTPUT);
+
+        let list = apply_query(processes.clone(), &ProcessQuery::default());
+        assert_eq!(list.total, 3);
+        assert_eq!(list.processes[0].pid, 812);
+
+        let query = ProcessQuery {
+            filter: Some("BASH".to_string()),
+            ..Default::default()
+        };
+        let list = apply_query(processes.clone(), &query);
+        assert_eq!(list.processes.len(), 1);
+        assert_eq!(list.processes[0].pid, 900);
+
+        let query = ProcessQuery {
+            sort_by: ProcessSort::Started,
+            descending: false,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let pids: Vec<u32> = apply_query(processes, &query)
+            .processes
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(pids, [1, 900]);
+    }
+
+    #[test]
+    fn test_signal_command() {
+        let (command, input) = signal_command(&[12, 34], ProcessSignal::Term, None);
+        assert!(command.starts_with("sh -c 'for pid in 12 34; do"));
+        assert!(command.contains("kill -TERM $pid"));
+        assert!(input.is_none());
+
+        let sudo = SudoOptions {
+            password: Some("secret".to_string()),
+        };
+        let (command, input) = signal_command(&[12], ProcessSignal::Kill, Some(&sudo));
+        assert!(command.starts_with("sudo -S -p '' sh -c '"));
+        assert_eq!(input.as_deref(), Some("secret\n"));
+
+        let results = parse_signal_output(
+            "12 ok\n34 sh: kill: (34) - Operation not permitted\n",
+            &[12, 34, 56],
+        );
+        assert!(results[0].success);
+        assert_eq!(
+            results[1].error.as_deref(),
+            Some("sh: kill: (34) - Operation not permitted")
+        );
+        assert_eq!(results[2].error.as_deref(), Some("No result"));
+    }
+}
@@ -0,0 +1,603 @@
+use crate::utils::utf8::Utf8Decoder;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// 录像文件扩展名
+const CAST_EXTENSION: &str = "cast";
+
+/// 读取录像时长时从文件末尾读取的字节数
+const TAIL_READ_SIZE: u64 = 64 * 1024;
+
+/// 录像文件的落盘间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// asciicast v2 文件头
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastHeader {
+    pub version: u8,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// 录像信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingInfo {
+    pub recording_id: String,
+    pub session_id: Option<String>,
+    pub file_name: String,
+    pub path: String,
+    pub title: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// 开始录制的时间（Unix 时间戳，秒）
+    pub started_at: Option<i64>,
+    /// 录像时长（秒）
+    pub duration: f64,
+    pub size: u64,
+    pub include_input: bool,
+    pub active: bool,
+}
+
+/// 发送给写入线程的消息
+enum WriterMessage {
+    /// 一条事件（不含换行）
+    Event(String),
+    /// 写入剩余数据并落盘后结束，返回结果
+    Finish(oneshot::Sender<std::io::Result<()>>),
+}
+
+/// 正在进行的录制
+struct ActiveRecording {
+    info: RecordingInfo,
+    writer: mpsc::Sender<WriterMessage>,
+    started: Instant,
+    output: Utf8Decoder,
+    input: Utf8Decoder,
+}
+
+impl ActiveRecording {
+    /// 写入一条事件 [time, code, data]（由写入线程写入文件）
+    fn write_event(&mut self, code: &str, data: &str) -> std::io::Result<()> {
+        let time = elapsed_secs(self.started);
+        let line = json!([time, code, data]).to_string();
+        let size = line.len() as u64 + 1;
+        self.writer
+            .send(WriterMessage::Event(line))
+            .map_err(|_| std::io::Error::other("recording writer stopped"))?;
+
+        self.info.duration = time;
+        self.info.size += size;
+        Ok(())
+    }
+}
+
+/// 启动录像文件的写入线程
+/// - 终端输出在会话的读取任务中产生，文件 IO 放到单独的线程，避免阻塞异步运行时
+/// - 距上次落盘超过 FLUSH_INTERVAL 时落盘，应用异常退出时最多丢失最后一秒的事件
+/// - 写入失败时记录日志并退出，之后的事件发送失败，录制随之停止
+fn spawn_writer(path: String, mut writer: BufWriter<File>) -> mpsc::Sender<WriterMessage> {
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let mut dirty = false;
+        let mut last_flush = Instant::now();
+        loop {
+            let message = if dirty {
+                rx.recv_timeout(FLUSH_INTERVAL.saturating_sub(last_flush.elapsed()))
+            } else {
+                rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            };
+
+            let mut result = match message {
+                Ok(WriterMessage::Event(line)) => {
+                    dirty = true;
+                    writer
+                        .write_all(line.as_bytes())
+                        .and_then(|_| writer.write_all(b"\n"))
+                }
+                Ok(WriterMessage::Finish(reply)) => {
+                    let _ = reply.send(writer.flush());
+                    return;
+                }
+                Err(RecvTimeoutError::Timeout) => Ok(()),
+                Err(RecvTimeoutError::Disconnected) => {
+                    let _ = writer.flush();
+                    return;
+                }
+            };
+
+            if result.is_ok() && dirty && last_flush.elapsed() >= FLUSH_INTERVAL {
+                result = writer.flush();
+                dirty = false;
+                last_flush = Instant::now();
+            }
+            if let Err(e) = result {
+                log::warn!("Failed to write recording {}: {}", path, e);
+                return;
+            }
+        }
+    });
+
+    tx
+}
+
+/// 终端录像管理器
+/// - 每个会话最多一个进行中的录制，输出以 "o" 事件记录
+/// - include_input 为 true 时同时以 "i" 事件记录用户输入
+/// - 窗口大小变化以 "r" 事件记录
+pub struct RecordingManager {
+    active: Mutex<HashMap<String, ActiveRecording>>,
+}
+
+impl RecordingManager {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 开始录制会话
+    /// - 创建文件和写入文件头在阻塞线程池中执行，不持有录制表的锁
+    pub async fn start(
+        &self,
+        session_id: &str,
+        dir: &Path,
+        width: u32,
+        height: u32,
+        title: Option<String>,
+        include_input: bool,
+    ) -> Result<RecordingInfo> {
+        if self.is_recording(session_id) {
+            return Err(anyhow!("Session is already being recorded: {}", session_id));
+        }
+
+        let now = Utc::now();
+        let short_id: String = session_id.chars().take(8).collect();
+        let recording_id = format!("{}-{}", now.format("%Y%m%d-%H%M%S%3f"), short_id);
+        let file_name = format!("{}.{}", recording_id, CAST_EXTENSION);
+        let path = dir.join(&file_name);
+
+        let header = CastHeader {
+            version: 2,
+            width,
+            height,
+            timestamp: Some(now.timestamp()),
+            title: title.clone(),
+            env: Some(HashMap::from([(
+                "TERM".to_string(),
+                "xterm-256color".to_string(),
+            )])),
+        };
+        let header_line = serde_json::to_string(&header)?;
+
+        let writer = {
+            let dir = dir.to_path_buf();
+            let path = path.clone();
+            let header_line = header_line.clone();
+            tokio::task::spawn_blocking(move || create_recording_file(&dir, &path, &header_line))
+                .await
+                .map_err(|e| anyhow!("Failed to create recording file: {}", e))??
+        };
+
+        let info = RecordingInfo {
+            recording_id,
+            session_id: Some(session_id.to_string()),
+            file_name,
+            path: path.to_string_lossy().into_owned(),
+            title,
+            width,
+            height,
+            started_at: header.timestamp,
+            duration: 0.0,
+            size: header_line.len() as u64 + 1,
+            include_input,
+            active: true,
+        };
+
+        {
+            let mut active = self.active.lock().unwrap();
+            // 创建文件期间可能有另一次 start 已开始录制
+            if !active.contains_key(session_id) {
+                active.insert(
+                    session_id.to_string(),
+                    ActiveRecording {
+                        writer: spawn_writer(info.path.clone(), writer),
+                        info: info.clone(),
+                        started: Instant::now(),
+                        output: Utf8Decoder::new(),
+                        input: Utf8Decoder::new(),
+                    },
+                );
+                return Ok(info);
+            }
+        }
+
+        drop(writer);
+        let _ = tokio::fs::remove_file(&path).await;
+        Err(anyhow!("Session is already being recorded: {}", session_id))
+    }
+
+    /// 停止录制会话（等待写入线程写完剩余事件）
+    pub async fn stop(&self, session_id: &str) -> Result<RecordingInfo> {
+        let recording = self
+            .active
+            .lock()
+            .unwrap()
+            .remove(session_id)
+            .ok_or_else(|| anyhow!("Session is not being recorded: {}", session_id))?;
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        recording
+            .writer
+            .send(WriterMessage::Finish(reply_tx))
+            .map_err(|_| anyhow!("Failed to flush recording: writer stopped"))?;
+        reply_rx
+            .await
+            .map_err(|_| anyhow!("Failed to flush recording: writer stopped"))?
+            .map_err(|e| anyhow!("Failed to flush recording: {}", e))?;
+
+        let mut info = recording.info;
+        info.active = false;
+        Ok(info)
+    }
+
+    /// 会话是否正在录制
+    pub fn is_recording(&self, session_id: &str) -> bool {
+        self.active.lock().unwrap().contains_key(session_id)
+    }
+
+    /// 记录终端输出
+    pub fn record_output(&self, session_id: &str, data: &[u8]) {
+        self.record(session_id, |recording| {
+            let text = recording.output.decode(data);
+            if text.is_empty() {
+                return Ok(());
+            }
+            recording.write_event("o", &text)
+        });
+    }
+
+    /// 记录用户输入（仅在开启 include_input 时记录）
+    pub fn record_input(&self, session_id: &str, data: &[u8]) {
+        self.record(session_id, |recording| {
+            if !recording.info.include_input {
+                return Ok(());
+            }
+            let text = recording.input.decode(data);
+            if text.is_empty() {
+                return Ok(());
+            }
+            recording.write_event("i", &text)
+        });
+    }
+
+    /// 记录窗口大小变化
+    pub fn record_resize(&self, session_id: &str, cols: u32, rows: u32) {
+        self.record(session_id, |recording| {
+            recording.write_event("r", &format!("{}x{}", cols, rows))
+        });
+    }
+
+    /// 写入失败时停止该会话的录制，避免每次输出都重复报错
+    fn record(
+        &self,
+        session_id: &str,
+        write: impl FnOnce(&mut ActiveRecording) -> std::io::Result<()>,
+    ) {
+        let mut active = self.active.lock().unwrap();
+        let Some(recording) = active.get_mut(session_id) else {
+            return;
+        };
+
+        if let Err(e) = write(recording) {
+            log::warn!(
+                "Failed to write recording {}, recording stopped: {}",
+                recording.info.path,
+                e
+            );
+            active.remove(session_id);
+        }
+    }
+
+    /// 列出录像目录中的所有录像（最新的在前）
+    pub fn list(&self, dir: &Path) -> Result<Vec<RecordingInfo>> {
+        let active: HashMap<PathBuf, RecordingInfo> = self
+            .active
+            .lock()
+            .unwrap()
+            .values()
+            .map(|r| (PathBuf::from(&r.info.path), r.info.clone()))
+            .collect();
+
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries =
+            std::fs::read_dir(dir).map_err(|e| anyhow!("Failed to read recordings dir: {}", e))?;
+
+        let mut recordings = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(CAST_EXTENSION) {
+                continue;
+            }
+
+            if let Some(info) = active.get(&path) {
+                recordings.push(info.clone());
+                continue;
+            }
+
+            match read_recording_info(&path) {
+                Ok(info) => recordings.push(info),
+                Err(e) => log::warn!("Skipping invalid recording {}: {}", path.display(), e),
+            }
+        }
+
+        recordings.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        Ok(recordings)
+    }
+}
+
+/// 创建录像文件并写入文件头
+/// - 使用 create_new，文件名冲突时不覆盖已有录像
+fn create_recording_file(dir: &Path, path: &Path, header_line: &str) -> Result<BufWriter<File>> {
+    std::fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create recordings dir: {}", e))?;
+
+    let file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to create recording file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "{}", header_line)
+        .and_then(|_| writer.flush())
+        .map_err(|e| anyhow!("Failed to write recording header: {}", e))?;
+    Ok(writer)
+}
+
+/// 录像目录（应用数据目录下的 recordings）
+pub fn recordings_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    let app_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| anyhow!("Failed to get app data dir"))?;
+    Ok(app_dir.join("recordings"))
+}
+
+/// 根据录像 ID 获取录像文件路径（ID 不允许包含路径分隔符）
+pub fn recording_path(dir: &Path, recording_id: &str) -> Result<PathBuf> {
+    if recording_id.is_empty()
+        || recording_id.contains(['/', '\\'])
+        || recording_id.starts_with('.')
+    {
+        return Err(anyhow!("Invalid recording id: {}", recording_id));
+    }
+
+    let path = dir.join(format!("{}.{}", recording_id, CAST_EXTENSION));
+    if !path.is_file() {
+        return Err(anyhow!("Recording not found: {}", recording_id));
+    }
+    Ok(path)
+}
+
+/// 读取录像文件的头和时长
+pub fn read_recording_info(path: &Path) -> Result<RecordingInfo> {
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open recording: {}", e))?;
+    let size = file.metadata()?.len();
+
+    let mut header_line = String::new();
+    BufReader::new(&file).read_line(&mut header_line)?;
+    let header: CastHeader = serde_json::from_str(header_line.trim())
+        .map_err(|e| anyhow!("Invalid asciicast header: {}", e))?;
+    if header.version != 2 {
+        return Err(anyhow!("Unsupported asciicast version: {}", header.version));
+    }
+
+    let duration = last_event_time(&file, size).unwrap_or(0.0);
+    let recording_id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(RecordingInfo {
+        recording_id,
+        session_id: None,
+        file_name: path
+            .file_name()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().into_owned(),
+        title: header.title,
+        width: header.width,
+        height: header.height,
+        started_at: header.timestamp,
+        duration,
+        size,
+        include_input: false,
+        active: false,
+    })
+}
+
+/// 从文件末尾找到最后一条事件的时间
+fn last_event_time(mut file: &File, size: u64) -> Option<f64> {
+    let start = size.saturating_sub(TAIL_READ_SIZE);
+    file.seek(SeekFrom::Start(start)).ok()?;
+
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+
+    String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .find_map(parse_event_time)
+}
+
+/// 解析事件行的时间字段，非事件行返回 None
+pub fn parse_event_time(line: &str) -> Option<f64> {
+    let line = line.trim();
+    if !line.starts_with('[') {
+        return None;
+    }
+    let event: serde_json::Value = serde_json::from_str(line).ok()?;
+    event.get(0)?.as_f64()
+}
+
+/// 距开始录制的秒数（保留到微秒）
+fn elapsed_secs(started: Instant) -> f64 {
+    (started.elapsed().as_secs_f64() * 1_000_000.0).round() / 1_000_000.0
+}
+
+/// 全局录像管理器
+static RECORDING_MANAGER: Lazy<RecordingManager> = Lazy::new(RecordingManager::new);
+
+pub fn get_recording_manager() -> &'static RecordingManager {
+    &RECORDING_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("devhub-recorder-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_event_time() {
+        assert_eq!(parse_event_time(r#"[1.5, "o", "ls\r\n"]"#), Some(1.5));
+        assert_eq!(parse_event_time(r#"{"version": 2}"#), None);
+        assert_eq!(parse_event_time(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_record_and_read_back() {
+        let dir = temp_dir("roundtrip");
+        let manager = RecordingManager::new();
+
+        let info = manager
+            .start("session-1", &dir, 80, 24, Some("deploy".to_string()), false)
+            .await
+            .unwrap();
+        assert!(manager.is_recording("session-1"));
+        assert!(manager
+            .start("session-1", &dir, 80, 24, None, false)
+            .await
+            .is_err());
+
+        manager.record_output("session-1", "部署".as_bytes());
+        manager.record_input("session-1", b"ignored");
+        manager.record_resize("session-1", 120, 40);
+        let stopped = manager.stop("session-1").await.unwrap();
+        assert!(!stopped.active);
+        assert!(!manager.is_recording("session-1"));
+
+        let content = std::fs::read_to_string(&info.path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+
+        let header: CastHeader = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(header.version, 2);
+        assert_eq!((header.width, header.height), (80, 24));
+        assert_eq!(header.title.as_deref(), Some("deploy"));
+
+        let output: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(output[1], "o");
+        assert_eq!(output[2], "部署");
+        let resize: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(resize[1], "r");
+        assert_eq!(resize[2], "120x40");
+
+        let listed = manager.list(&dir).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].recording_id, info.recording_id);
+        assert_eq!(listed[0].size, stopped.size);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_events_flushed_without_stopping() {
+        let dir = temp_dir("flush");
+        let manager = RecordingManager::new();
+
+        let info = manager
+            .start("session-3", &dir, 80, 24, None, false)
+            .await
+            .unwrap();
+        manager.record_output("session-3", b"tail -f app.log\r\n");
+
+        // 写入线程按间隔落盘，录制进行中文件里也能读到事件
+        let deadline = Instant::now() + FLUSH_INTERVAL * 5;
+        let mut lines = 0;
+        while Instant::now() < deadline {
+            lines = std::fs::read_to_string(&info.path).unwrap().lines().count();
+            if lines == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(lines, 2);
+
+        manager.stop("session-3").await.unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_input_when_enabled() {
+        let dir = temp_dir("input");
+        let manager = RecordingManager::new();
+
+        let info = manager
+            .start("session-2", &dir, 80, 24, None, true)
+            .await
+            .unwrap();
+        manager.record_input("session-2", b"uptime\r");
+        manager.stop("session-2").await.unwrap();
+
+        let content = std::fs::read_to_string(&info.path).unwrap();
+        let event: serde_json::Value =
+            serde_json::from_str(content.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(event[1], "i");
+        assert_eq!(event[2], "uptime\r");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_start_keeps_one_recording() {
+        let dir = temp_dir("concurrent");
+        let manager = RecordingManager::new();
+
+        let (first, second) = tokio::join!(
+            manager.start("session-4", &dir, 80, 24, None, false),
+            manager.start("session-4", &dir, 80, 24, None, false),
+        );
+        assert!(first.is_ok() != second.is_ok());
+        manager.stop("session-4").await.unwrap();
+
+        // 冲突的一方不留下文件
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
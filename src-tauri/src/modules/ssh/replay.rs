@@ -0,0 +1,537 @@
+use super::recorder::CastHeader;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// 清屏并重置终端（seek 时先重置，再重放目标位置之前最后一次清屏之后的输出）
+const TERMINAL_RESET: &str = "\x1bc";
+
+/// 清屏序列，seek 时从目标位置之前最后一次清屏处开始重放
+/// - \x1b[3J 只清除回滚区、不清除屏幕，从它开始重放会丢失屏幕内容，因此不作为检查点
+const CLEAR_SEQUENCES: [&str; 2] = ["\x1b[2J", "\x1bc"];
+
+/// seek 时每个 ssh-data 事件最多推送的字节数
+const SEEK_CHUNK_BYTES: usize = 64 * 1024;
+
+/// 最大/最小播放速度
+const MIN_SPEED: f64 = 0.1;
+const MAX_SPEED: f64 = 16.0;
+
+/// 录像中的一帧
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastFrame {
+    pub time: f64,
+    pub code: String,
+    pub data: String,
+}
+
+/// 清屏检查点：frames[index].data 从 start 字节处开始的输出与之前的输出无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Checkpoint {
+    index: usize,
+    start: usize,
+}
+
+/// 解析后的录像
+#[derive(Debug, Clone)]
+pub struct CastRecording {
+    pub header: CastHeader,
+    pub frames: Vec<CastFrame>,
+    /// 按帧顺序排列的清屏检查点（加载时计算）
+    checkpoints: Vec<Checkpoint>,
+    /// 按帧顺序排列的窗口大小变化（帧下标，大小）
+    resizes: Vec<(usize, (u32, u32))>,
+}
+
+impl CastRecording {
+    /// 解析 asciicast v2 内容
+    pub fn parse(reader: impl BufRead) -> Result<Self> {
+        let mut lines = reader.lines();
+
+        let header_line = lines
+            .next()
+            .ok_or_else(|| anyhow!("Recording is empty"))??;
+        let header: CastHeader = serde_json::from_str(header_line.trim())
+            .map_err(|e| anyhow!("Invalid asciicast header: {}", e))?;
+        if header.version != 2 {
+            return Err(anyhow!("Unsupported asciicast version: {}", header.version));
+        }
+
+        let mut frames = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (time, code, data): (f64, String, String) = serde_json::from_str(line)
+                .map_err(|e| anyhow!("Invalid event on line {}: {}", index + 2, e))?;
+            frames.push(CastFrame { time, code, data });
+        }
+
+        // 录制时事件按时间写入，这里保证有序以便二分查找
+        frames.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        let mut checkpoints = Vec::new();
+        let mut resizes = Vec::new();
+        for (index, frame) in frames.iter().enumerate() {
+            match frame.code.as_str() {
+                "o" => {
+                    let last_clear = CLEAR_SEQUENCES
+                        .iter()
+                        .filter_map(|sequence| frame.data.rfind(sequence))
+                        .max();
+                    if let Some(start) = last_clear {
+                        checkpoints.push(Checkpoint { index, start });
+                    }
+                }
+                "r" => {
+                    if let Some(size) = parse_size(&frame.data) {
+                        resizes.push((index, size));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            header,
+            frames,
+            checkpoints,
+            resizes,
+        })
+    }
+
+    /// 从文件加载录像
+    pub fn load(path: &Path) -> Result<Self> {
+        let file =
+            std::fs::File::open(path).map_err(|e| anyhow!("Failed to open recording: {}", e))?;
+        Self::parse(BufReader::new(file))
+    }
+
+    /// 录像时长（秒）
+    pub fn duration(&self) -> f64 {
+        self.frames.last().map(|f| f.time).unwrap_or(0.0)
+    }
+
+    /// 第一个时间晚于 position 的帧的下标
+    pub fn next_index(&self, position: f64) -> usize {
+        self.frames.partition_point(|f| f.time <= position)
+    }
+
+    /// 计算 seek 到 position 时需要一次性输出的内容和终端大小
+    /// - 输出从 position 之前最后一次清屏开始，不必每次从头拼接全部输出
+    pub fn snapshot(&self, position: f64) -> (String, (u32, u32)) {
+        let end = self.next_index(position);
+
+        let resized = self.resizes.partition_point(|(index, _)| *index < end);
+        let size = match resized {
+            0 => (self.header.width, self.header.height),
+            n => self.resizes[n - 1].1,
+        };
+
+        let checkpoint = match self.checkpoints.partition_point(|c| c.index < end) {
+            0 => Checkpoint { index: 0, start: 0 },
+            n => self.checkpoints[n - 1],
+        };
+
+        let mut output = String::from(TERMINAL_RESET);
+        for (index, frame) in self.frames[..end].iter().enumerate().skip(checkpoint.index) {
+            if frame.code != "o" {
+                continue;
+            }
+            if index == checkpoint.index {
+                output.push_str(&frame.data[checkpoint.start..]);
+            } else {
+                output.push_str(&frame.data);
+            }
+        }
+
+        (output, size)
+    }
+}
+
+/// 按字符边界把 data 切成不超过 max_bytes 的片段
+fn split_chunks(data: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+/// 解析 "r" 事件的 "{cols}x{rows}"
+fn parse_size(data: &str) -> Option<(u32, u32)> {
+    let (cols, rows) = data.split_once('x')?;
+    Some((cols.trim().parse().ok()?, rows.trim().parse().ok()?))
+}
+
+/// 回放状态
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayState {
+    Playing,
+    Paused,
+    Finished,
+}
+
+/// 回放信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayInfo {
+    /// 虚拟会话 ID，输出通过 ssh-data-{replay_id} 事件推送（与会话输出相同的 { offset, data }）
+    pub replay_id: String,
+    pub recording_id: String,
+    pub title: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub duration: f64,
+}
+
+/// 回放控制命令
+enum ReplayCommand {
+    Play,
+    Pause,
+    Seek(f64),
+    Speed(f64),
+}
+
+/// 单个回放任务的状态
+struct Player {
+    replay_id: String,
+    recording: CastRecording,
+    app_handle: tauri::AppHandle,
+    state: ReplayState,
+    position: f64,
+    index: usize,
+    speed: f64,
+    /// 已推送的输出字节数
+    offset: u64,
+}
+
+impl Player {
+    fn emit_data(&mut self, data: &str) {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(data.as_bytes());
+        let _ = self.app_handle.emit_all(
+            &format!("ssh-data-{}", self.replay_id),
+            json!({ "offset": self.offset, "data": encoded }),
+        );
+        self.offset += data.len() as u64;
+    }
+
+    fn emit_resize(&self, (cols, rows): (u32, u32)) {
+        let _ = self.app_handle.emit_all(
+            &format!("ssh-replay-resize-{}", self.replay_id),
+            json!({ "cols": cols, "rows": rows }),
+        );
+    }
+
+    fn emit_state(&self) {
+        let _ = self.app_handle.emit_all(
+            &format!("ssh-replay-state-{}", self.replay_id),
+            json!({
+                "replay_id": self.replay_id,
+                "state": self.state,
+                "position": self.position,
+                "duration": self.recording.duration(),
+                "speed": self.speed,
+            }),
+        );
+    }
+
+    fn emit_frame(&mut self, frame: &CastFrame) {
+        match frame.code.as_str() {
+            "o" => self.emit_data(&frame.data),
+            "r" => {
+                if let Some(size) = parse_size(&frame.data) {
+                    self.emit_resize(size);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn seek(&mut self, position: f64) {
+        let position = position.clamp(0.0, self.recording.duration());
+        let (output, size) = self.recording.snapshot(position);
+
+        self.emit_resize(size);
+        for chunk in split_chunks(&output, SEEK_CHUNK_BYTES) {
+            self.emit_data(chunk);
+        }
+        self.position = position;
+        self.index = self.recording.next_index(position);
+        if self.index >= self.recording.frames.len() {
+            self.state = ReplayState::Finished;
+        } else if self.state == ReplayState::Finished {
+            self.state = ReplayState::Paused;
+        }
+    }
+
+    fn handle(&mut self, command: ReplayCommand) {
+        match command {
+            ReplayCommand::Play => {
+                if self.state == ReplayState::Finished {
+                    self.seek(0.0);
+                }
+                self.state = ReplayState::Playing;
+            }
+            ReplayCommand::Pause => {
+                if self.state == ReplayState::Playing {
+                    self.state = ReplayState::Paused;
+                }
+            }
+            ReplayCommand::Seek(position) => self.seek(position),
+            ReplayCommand::Speed(speed) => self.speed = speed.clamp(MIN_SPEED, MAX_SPEED),
+        }
+        self.emit_state();
+    }
+
+    /// 按录像时间依次推送帧，直到控制通道关闭
+    async fn run(mut self, mut commands: mpsc::UnboundedReceiver<ReplayCommand>) {
+        loop {
+            if self.state != ReplayState::Playing {
+                match commands.recv().await {
+                    Some(command) => self.handle(command),
+                    None => break,
+                }
+                continue;
+            }
+
+            let Some(frame) = self.recording.frames.get(self.index).cloned() else {
+                self.state = ReplayState::Finished;
+                self.emit_state();
+                continue;
+            };
+
+            let wait = ((frame.time - self.position).max(0.0)) / self.speed;
+            let started = Instant::now();
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs_f64(wait)) => {
+                    self.emit_frame(&frame);
+                    self.position = frame.time;
+                    self.index += 1;
+                }
+                command = commands.recv() => {
+                    // 记录被打断时已经播放到的位置
+                    let played = started.elapsed().as_secs_f64() * self.speed;
+                    self.position = (self.position + played).min(frame.time);
+                    match command {
+                        Some(command) => self.handle(command),
+                        None => break,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 录像回放管理器
+/// - 每个回放使用一个虚拟会话 ID，复用终端组件的 ssh-data-{id} 事件
+pub struct ReplayManager {
+    players: Mutex<HashMap<String, mpsc::UnboundedSender<ReplayCommand>>>,
+}
+
+impl ReplayManager {
+    pub fn new() -> Self {
+        Self {
+            players: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 加载录像并创建回放（初始为暂停状态）
+    pub async fn open(
+        &self,
+        recording_id: &str,
+        path: &Path,
+        app_handle: tauri::AppHandle,
+    ) -> Result<ReplayInfo> {
+        let recording = CastRecording::load(path)?;
+        let replay_id = format!("replay-{}", Uuid::new_v4());
+
+        let info = ReplayInfo {
+            replay_id: replay_id.clone(),
+            recording_id: recording_id.to_string(),
+            title: recording.header.title.clone(),
+            width: recording.header.width,
+            height: recording.header.height,
+            duration: recording.duration(),
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let player = Player {
+            replay_id: replay_id.clone(),
+            recording,
+            app_handle,
+            state: ReplayState::Paused,
+            position: 0.0,
+            index: 0,
+            speed: 1.0,
+            offset: 0,
+        };
+        tokio::spawn(player.run(rx));
+
+        self.players.lock().await.insert(replay_id, tx);
+        Ok(info)
+    }
+
+    pub async fn play(&self, replay_id: &str) -> Result<()> {
+        self.send(replay_id, ReplayCommand::Play).await
+    }
+
+    pub async fn pause(&self, replay_id: &str) -> Result<()> {
+        self.send(replay_id, ReplayCommand::Pause).await
+    }
+
+    /// 跳转到指定时间（秒）
+    pub async fn seek(&self, replay_id: &str, position: f64) -> Result<()> {
+        if !position.is_finite() {
+            return Err(anyhow!("Invalid position: {}", position));
+        }
+        self.send(replay_id, ReplayCommand::Seek(position)).await
+    }
+
+    /// 设置播放速度倍数
+    pub async fn set_speed(&self, replay_id: &str, speed: f64) -> Result<()> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(anyhow!("Invalid speed: {}", speed));
+        }
+        self.send(replay_id, ReplayCommand::Speed(speed)).await
+    }
+
+    /// 关闭回放（丢弃控制通道后回放任务自动退出）
+    pub async fn close(&self, replay_id: &str) -> Result<()> {
+        self.players
+            .lock()
+            .await
+            .remove(replay_id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Replay not found: {}", replay_id))
+    }
+
+    async fn send(&self, replay_id: &str, command: ReplayCommand) -> Result<()> {
+        let players = self.players.lock().await;
+        let tx = players
+            .get(replay_id)
+            .ok_or_else(|| anyhow!("Replay not found: {}", replay_id))?;
+
+        tx.send(command)
+            .map_err(|_| anyhow!("Replay has stopped: {}", replay_id))
+    }
+}
+
+/// 全局回放管理器
+static REPLAY_MANAGER: Lazy<ReplayManager> = Lazy::new(ReplayManager::new);
+
+pub fn get_replay_manager() -> &'static ReplayManager {
+    &REPLAY_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAST: &str = r#"{"version": 2, "width": 80, "height": 24, "timestamp": 1700000000}
+[0.5, "o", "$ "]
+[1.0, "i", "ls\r"]
+[1.2, "o", "ls\r\n"]
+[2.0, "r", "120x40"]
+[3.5, "o", "README.md\r\n"]
+"#;
+
+    fn recording() -> CastRecording {
+        CastRecording::parse(CAST.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_recording() {
+        let recording = recording();
+        assert_eq!(recording.header.width, 80);
+        assert_eq!(recording.frames.len(), 5);
+        assert_eq!(recording.frames[3].code, "r");
+        assert_eq!(recording.duration(), 3.5);
+    }
+
+    #[test]
+    fn test_parse_rejects_other_versions() {
+        let cast = r#"{"version": 1, "width": 80, "height": 24}"#;
+        assert!(CastRecording::parse(cast.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_next_index() {
+        let recording = recording();
+        assert_eq!(recording.next_index(0.0), 0);
+        assert_eq!(recording.next_index(1.2), 3);
+        assert_eq!(recording.next_index(10.0), 5);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let recording = recording();
+
+        let (output, size) = recording.snapshot(1.5);
+        assert_eq!(output, "\x1bc$ ls\r\n");
+        assert_eq!(size, (80, 24));
+
+        let (output, size) = recording.snapshot(3.5);
+        assert_eq!(output, "\x1bc$ ls\r\nREADME.md\r\n");
+        assert_eq!(size, (120, 40));
+    }
+
+    #[test]
+    fn test_snapshot_starts_at_last_clear() {
+        let cast = r#"{"version": 2, "width": 80, "height": 24}
+[0.5, "o", "old output\r\n"]
+[1.0, "r", "100x30"]
+[1.5, "o", "$ clear\r\n\u001b[H\u001b[2J$ "]
+[2.0, "o", "top\r\n"]
+[3.0, "o", "\u001bcfresh"]
+"#;
+        let recording = CastRecording::parse(cast.as_bytes()).unwrap();
+
+        let (output, size) = recording.snapshot(1.0);
+        assert_eq!(output, "\x1bcold output\r\n");
+        assert_eq!(size, (100, 30));
+
+        let (output, _) = recording.snapshot(2.5);
+        assert_eq!(output, "\x1bc\x1b[2J$ top\r\n");
+
+        let (output, size) = recording.snapshot(3.0);
+        assert_eq!(output, "\x1bc\x1bcfresh");
+        assert_eq!(size, (100, 30));
+    }
+
+    #[test]
+    fn test_split_chunks() {
+        assert_eq!(split_chunks("", 4), vec![""]);
+        assert_eq!(split_chunks("abcdefgh", 4), vec!["abcd", "efgh"]);
+        // 不拆开多字节字符
+        assert_eq!(split_chunks("ab部署", 4), vec!["ab", "部", "署"]);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("120x40"), Some((120, 40)));
+        assert_eq!(parse_size("bad"), None);
+    }
+}
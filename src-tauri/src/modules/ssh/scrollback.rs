@@ -0,0 +1,211 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 一次读取最多返回的字节数
+const MAX_READ_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Default)]
+struct ScrollbackState {
+    buffer: VecDeque<u8>,
+    /// 缓冲区第一个字节在会话输出中的偏移量（之前的输出已被丢弃）
+    start: u64,
+}
+
+impl ScrollbackState {
+    fn end(&self) -> u64 {
+        self.start + self.buffer.len() as u64
+    }
+}
+
+/// 从缓冲区读取的一段输出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrollbackRead {
+    pub data: Vec<u8>,
+    /// data 第一个字节的偏移量
+    pub offset: u64,
+    /// 下一次读取应使用的偏移量
+    pub next_offset: u64,
+    /// 请求的偏移量之前的输出已被丢弃，返回的数据不连续
+    pub truncated: bool,
+}
+
+/// 返回给前端的回滚缓冲区内容
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrollbackChunk {
+    /// Base64 编码的输出
+    pub data: String,
+    pub offset: u64,
+    pub next_offset: u64,
+    pub truncated: bool,
+}
+
+impl From<ScrollbackRead> for ScrollbackChunk {
+    fn from(read: ScrollbackRead) -> Self {
+        use base64::Engine;
+        Self {
+            data: base64::engine::general_purpose::STANDARD.encode(&read.data),
+            offset: read.offset,
+            next_offset: read.next_offset,
+            truncated: read.truncated,
+        }
+    }
+}
+
+/// 会话输出的回滚缓冲区（环形，保留最近 capacity 字节）
+/// - 偏移量从会话开始累计，重连后继续递增，前端按偏移量续读不会丢失或重复输出
+/// - 每批输出通过 ssh-data-{id} 事件推送时带上偏移量
+pub struct Scrollback {
+    state: Mutex<ScrollbackState>,
+    capacity: usize,
+}
+
+impl Scrollback {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(ScrollbackState::default()),
+            capacity,
+        }
+    }
+
+    /// 追加一批输出，返回这批输出的起始偏移量
+    pub fn push(&self, data: &[u8]) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let offset = state.end();
+
+        if data.len() >= self.capacity {
+            let keep = &data[data.len() - self.capacity..];
+            state.buffer.clear();
+            state.buffer.extend(keep);
+            state.start = offset + (data.len() - keep.len()) as u64;
+            return offset;
+        }
+
+        state.buffer.extend(data);
+        let excess = state.buffer.len().saturating_sub(self.capacity);
+        if excess > 0 {
+            state.buffer.drain(..excess);
+            state.start += excess as u64;
+        }
+        offset
+    }
+
+    /// 从指定偏移量读取（不传时从缓冲区开头读取）
+    /// - 偏移量早于缓冲区开头时从开头读取并标记 truncated
+    /// - 从被截断的开头读取（不传偏移量或偏移量已被丢弃）时跳过不完整的 UTF-8 字符，
+    ///   按上次的 next_offset 续读时原样返回（上次可能在字符中间按 max_bytes 截断）
+    pub fn read(&self, offset: Option<u64>, max_bytes: Option<usize>) -> ScrollbackRead {
+        let state = self.state.lock().unwrap();
+        let end = state.end();
+
+        let requested = offset.unwrap_or(state.start);
+        let truncated = requested < state.start;
+        let mut from = requested.clamp(state.start, end);
+
+        if state.start > 0 && (offset.is_none() || truncated) {
+            let skip = state
+                .buffer
+                .iter()
+                .take(3)
+                .take_while(|&&b| b & 0xC0 == 0x80)
+                .count();
+            from += skip as u64;
+        }
+
+        let max_bytes = max_bytes.unwrap_or(MAX_READ_BYTES).min(MAX_READ_BYTES);
+        let begin = (from - state.start) as usize;
+        let len = ((end - from) as usize).min(max_bytes);
+        let data: Vec<u8> = state.buffer.range(begin..begin + len).copied().collect();
+
+        ScrollbackRead {
+            data,
+            offset: from,
+            next_offset: from + len as u64,
+            truncated: truncated || from > requested,
+        }
+    }
+
+    /// 当前输出的结束偏移量
+    pub fn end_offset(&self) -> u64 {
+        self.state.lock().unwrap().end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_evicts_oldest() {
+        let scrollback = Scrollback::new(8);
+        assert_eq!(scrollback.push(b"hello"), 0);
+        assert_eq!(scrollback.push(b" world"), 5);
+
+        let read = scrollback.read(None, None);
+        assert_eq!(read.data, b"lo world");
+        assert_eq!(read.offset, 3);
+        assert_eq!(read.next_offset, 11);
+        assert!(!read.truncated);
+
+        // 单批超过容量时只保留末尾
+        assert_eq!(scrollback.push(b"0123456789"), 11);
+        let read = scrollback.read(None, None);
+        assert_eq!(read.data, b"23456789");
+        assert_eq!(read.offset, 13);
+        assert_eq!(scrollback.end_offset(), 21);
+    }
+
+    #[test]
+    fn test_read_from_offset() {
+        let scrollback = Scrollback::new(8);
+        scrollback.push(b"abcdefghij");
+
+        let read = scrollback.read(Some(5), Some(2));
+        assert_eq!(read.data, b"fg");
+        assert_eq!(read.next_offset, 7);
+        assert!(!read.truncated);
+
+        // 已被丢弃的偏移量
+        let read = scrollback.read(Some(0), None);
+        assert_eq!(read.data, b"cdefghij");
+        assert!(read.truncated);
+
+        // 已读到末尾
+        let read = scrollback.read(Some(10), None);
+        assert!(read.data.is_empty());
+        assert_eq!(read.next_offset, 10);
+        let read = scrollback.read(Some(99), None);
+        assert_eq!(read.offset, 10);
+    }
+
+    #[test]
+    fn test_read_skips_partial_utf8() {
+        let scrollback = Scrollback::new(4);
+        // "a中" = 61 e4 b8 ad，"文" = e6 96 87
+        scrollback.push("a中文".as_bytes());
+
+        let read = scrollback.read(None, None);
+        assert_eq!(read.data, "文".as_bytes());
+        assert_eq!(read.offset, 4);
+        assert!(read.truncated);
+
+        let read = scrollback.read(Some(0), None);
+        assert_eq!(read.data, "文".as_bytes());
+        assert!(read.truncated);
+    }
+
+    #[test]
+    fn test_read_continues_inside_utf8_char() {
+        // 上次按 max_bytes 读到 "中" 的中间，从缓冲区开头续读时不能跳过后续字节
+        let scrollback = Scrollback::new(8);
+        scrollback.push("ab中文".as_bytes());
+        let first = scrollback.read(Some(0), Some(3));
+        assert_eq!(first.data, b"ab\xe4");
+        scrollback.push(b"xyz");
+        // 缓冲区开头恰好落在 "中" 的第二个字节
+        let read = scrollback.read(Some(first.next_offset), None);
+        assert_eq!(read.offset, 3);
+        assert_eq!(read.data, b"\xb8\xad\xe6\x96\x87xyz");
+        assert!(!read.truncated);
+    }
+}
@@ -0,0 +1,624 @@
+use super::client::{get_ssh_manager, SSHClientHandler};
+use super::exec::run_command;
+use super::pool::{get_connection_pool, ConnectionLease};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use russh::client::Handle;
+use russh_sftp::client::fs::Metadata;
+use russh_sftp::client::SftpSession;
+use russh_sftp::protocol::{FileAttributes, FileType};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+/// 读取远程用户和组列表的命令
+/// - SFTP v3 只返回 uid/gid，用户名和组名从 getent 的输出解析（包含 LDAP 等 NSS 来源），
+///   没有 getent 时（如部分 BusyBox 系统）回退到 /etc/passwd、/etc/group
+const ID_NAMES_COMMAND: &str = "getent passwd 2>/dev/null || cat /etc/passwd 2>/dev/null; \
+     echo @@group; \
+     getent group 2>/dev/null || cat /etc/group 2>/dev/null";
+
+/// 读取用户和组列表的超时时间
+const ID_NAMES_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 文件类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SftpFileType {
+    File,
+    Dir,
+    Symlink,
+    Other,
+}
+
+impl From<FileType> for SftpFileType {
+    fn from(file_type: FileType) -> Self {
+        match file_type {
+            FileType::File => SftpFileType::File,
+            FileType::Dir => SftpFileType::Dir,
+            FileType::Symlink => SftpFileType::Symlink,
+            FileType::Other => SftpFileType::Other,
+        }
+    }
+}
+
+/// 远程目录项（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct SftpEntry {
+    pub name: String,
+    pub path: String,
+    pub file_type: SftpFileType,
+    pub size: u64,
+    /// 权限位（含 setuid/setgid/sticky，不含文件类型位）
+    pub mode: u32,
+    /// ls -l 风格的权限字符串，如 drwxr-xr-x
+    pub permissions: String,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// 所有者用户名（按 uid 在远程用户数据库中查找，找不到时为 None）
+    pub owner: Option<String>,
+    /// 所属组名（按 gid 在远程组数据库中查找，找不到时为 None）
+    pub group: Option<String>,
+    /// 修改时间（Unix 时间戳，秒）
+    pub mtime: Option<u32>,
+    pub symlink_target: Option<String>,
+}
+
+impl SftpEntry {
+    fn from_metadata(name: String, path: String, metadata: &Metadata, names: &IdNames) -> Self {
+        let file_type = SftpFileType::from(metadata.file_type());
+        let mode = metadata.permissions.unwrap_or(0) & 0o7777;
+
+        Self {
+            name,
+            path,
+            file_type,
+            size: metadata.size.unwrap_or(0),
+            mode,
+            permissions: format_permissions(file_type, mode),
+            uid: metadata.uid,
+            gid: metadata.gid,
+            owner: metadata.user.clone().or_else(|| names.user(metadata.uid?)),
+            group: metadata
+                .group
+                .clone()
+                .or_else(|| names.group(metadata.gid?)),
+            mtime: metadata.mtime,
+            symlink_target: None,
+        }
+    }
+}
+
+/// 远程主机上 uid/gid 对应的用户名和组名
+#[derive(Debug, Default)]
+struct IdNames {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl IdNames {
+    fn user(&self, uid: u32) -> Option<String> {
+        self.users.get(&uid).cloned()
+    }
+
+    fn group(&self, gid: u32) -> Option<String> {
+        self.groups.get(&gid).cloned()
+    }
+}
+
+/// 已打开的 SFTP 子系统
+struct SftpHandle {
+    sftp: Arc<SftpSession>,
+    names: Arc<IdNames>,
+    /// 通过连接池打开时持有连接的使用权，进行中的传输也会持有一份
+    lease: Option<Arc<ConnectionLease>>,
+    /// 打开时连接池中连接的代数，重连后需要重新打开
+    generation: u64,
+}
+
+/// 单个会话的 SFTP 子系统槽位
+/// - 打开、重新打开 SFTP 时只持有该会话的锁，不阻塞其他会话
+type SftpSlot = Arc<Mutex<Option<SftpHandle>>>;
+
+/// SFTP 会话管理器
+/// - 每个 SSH 会话按需打开一个 SFTP 子系统，复用会话已认证的连接
+/// - 也可以直接在已保存连接上打开（open_on_connection），不依赖终端会话，
+///   通过连接池共享连接，关闭且传输结束后释放
+pub struct SftpManager {
+    sessions: Mutex<HashMap<String, SftpSlot>>,
+}
+
+impl SftpManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 在已保存连接上打开 SFTP 子系统，返回 SFTP 会话 ID（用法与终端会话 ID 相同）
+    /// - 从连接池获取连接的使用权，close_session 后释放
+    pub async fn open_on_connection(&self, connection_id: &str) -> Result<String> {
+        let app_handle = get_ssh_manager().app_handle().await.ok();
+        let lease = get_connection_pool()
+            .acquire(connection_id, None, app_handle)
+            .await?;
+
+        let sftp = match open_sftp(&lease.transport()).await {
+            Ok(sftp) => sftp,
+            Err(e) => {
+                lease.release().await;
+                return Err(e);
+            }
+        };
+
+        let names = load_id_names(&lease.transport()).await;
+        let sftp_id = Uuid::new_v4().to_string();
+        let handle = SftpHandle {
+            sftp: Arc::new(sftp),
+            names: Arc::new(names),
+            generation: lease.generation(),
+            lease: Some(Arc::new(lease)),
+        };
+        self.sessions
+            .lock()
+            .await
+            .insert(sftp_id.clone(), Arc::new(Mutex::new(Some(handle))));
+        Ok(sftp_id)
+    }
+
+    /// 获取会话的 SFTP 子系统（不存在时打开）
+    /// - 通过连接池打开的 SFTP 在连接断开后重连，连接被替换后重新打开
+    pub async fn get_or_open(&self, session_id: &str) -> Result<Arc<SftpSession>> {
+        self.get_or_open_with_names(session_id)
+            .await
+            .map(|(sftp, _)| sftp)
+    }
+
+    /// 获取会话的 SFTP 子系统及远程用户名、组名
+    /// - 管理器的会话表只在取出槽位时短暂持有，打开 SFTP 和重连期间只锁住该会话的槽位
+    async fn get_or_open_with_names(
+        &self,
+        session_id: &str,
+    ) -> Result<(Arc<SftpSession>, Arc<IdNames>)> {
+        let slot = self
+            .sessions
+            .lock()
+            .await
+            .entry(session_id.to_string())
+            .or_default()
+            .clone();
+        let mut state = slot.lock().await;
+
+        if let Some(handle) = state.as_mut() {
+            let Some(lease) = &handle.lease else {
+                return Ok((handle.sftp.clone(), handle.names.clone()));
+            };
+
+            let app_handle = get_ssh_manager().app_handle().await.ok();
+            let (transport, generation) = get_connection_pool()
+                .reconnect(lease.connection_id(), app_handle)
+                .await?;
+            if generation != handle.generation {
+                handle.sftp = Arc::new(open_sftp(&transport).await?);
+                handle.generation = generation;
+            }
+            return Ok((handle.sftp.clone(), handle.names.clone()));
+        }
+
+        let opened = async {
+            let transport = get_ssh_manager().get_transport(session_id).await?;
+            let sftp = Arc::new(open_sftp(&transport).await?);
+            let names = Arc::new(load_id_names(&transport).await);
+            Ok::<_, anyhow::Error>((sftp, names))
+        }
+        .await;
+        let (sftp, names) = match opened {
+            Ok(opened) => opened,
+            Err(e) => {
+                drop(state);
+                self.remove_empty_slot(session_id, &slot).await;
+                return Err(e);
+            }
+        };
+
+        *state = Some(SftpHandle {
+            sftp: sftp.clone(),
+            names: names.clone(),
+            lease: None,
+            generation: 0,
+        });
+        Ok((sftp, names))
+    }
+
+    /// 打开失败时移除仍为空的槽位，避免不存在的会话留下条目
+    /// - 槽位正被其他调用者持有时由其负责，不在持有会话表锁时等待
+    async fn remove_empty_slot(&self, session_id: &str, slot: &SftpSlot) {
+        let mut sessions = self.sessions.lock().await;
+        let is_same_slot = sessions
+            .get(session_id)
+            .is_some_and(|current| Arc::ptr_eq(current, slot));
+        let is_empty = slot.try_lock().is_ok_and(|state| state.is_none());
+        if is_same_slot && is_empty {
+            sessions.remove(session_id);
+        }
+    }
+
+    /// 从会话表中取出会话的槽位
+    async fn slot(&self, session_id: &str) -> Option<SftpSlot> {
+        self.sessions.lock().await.get(session_id).cloned()
+    }
+
+    /// SFTP 所在连接的使用权（通过连接池打开时），传输在结束前持有以保持连接
+    pub async fn lease(&self, session_id: &str) -> Option<Arc<ConnectionLease>> {
+        let slot = self.slot(session_id).await?;
+        let state = slot.lock().await;
+        state.as_ref().and_then(|handle| handle.lease.clone())
+    }
+
+    /// 关闭 SFTP 子系统（会话断开或前端关闭通过连接池打开的 SFTP 时调用）
+    /// - 仍有传输在进行时，连接在传输结束后释放
+    /// - 正在打开时等待打开完成后再关闭
+    pub async fn close_session(&self, session_id: &str) {
+        let Some(slot) = self.sessions.lock().await.remove(session_id) else {
+            return;
+        };
+        let Some(handle) = slot.lock().await.take() else {
+            return;
+        };
+
+        let _ = handle.sftp.close().await;
+        if let Some(Ok(lease)) = handle.lease.map(Arc::try_unwrap) {
+            lease.release().await;
+        }
+    }
+
+    /// 丢弃会话的 SFTP 子系统但不发送关闭请求（连接已失效时调用，下次使用时重新打开）
+    pub async fn discard_session(&self, session_id: &str) {
+        self.sessions.lock().await.remove(session_id);
+    }
+
+    /// 列出目录
+    pub async fn list_dir(&self, session_id: &str, path: &str) -> Result<Vec<SftpEntry>> {
+        let (sftp, names) = self.get_or_open_with_names(session_id).await?;
+
+        let dir = sftp
+            .read_dir(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read directory {}: {}", path, e))?;
+
+        let mut entries = Vec::new();
+        for entry in dir {
+            let name = entry.file_name();
+            if name == "." || name == ".." {
+                continue;
+            }
+
+            let entry_path = join_path(path, &name);
+            let mut item = SftpEntry::from_metadata(name, entry_path, &entry.metadata(), &names);
+            if item.file_type == SftpFileType::Symlink {
+                item.symlink_target = sftp.read_link(item.path.as_str()).await.ok();
+            }
+            entries.push(item);
+        }
+
+        // 目录在前，按名称排序
+        entries.sort_by(|a, b| {
+            (b.file_type == SftpFileType::Dir)
+                .cmp(&(a.file_type == SftpFileType::Dir))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(entries)
+    }
+
+    /// 获取文件信息（不跟随符号链接）
+    pub async fn stat(&self, session_id: &str, path: &str) -> Result<SftpEntry> {
+        let (sftp, names) = self.get_or_open_with_names(session_id).await?;
+
+        let metadata = sftp
+            .symlink_metadata(path)
+            .await
+            .map_err(|e| anyhow!("Failed to stat {}: {}", path, e))?;
+
+        let mut entry =
+            SftpEntry::from_metadata(file_name(path), path.to_string(), &metadata, &names);
+        if entry.file_type == SftpFileType::Symlink {
+            entry.symlink_target = sftp.read_link(path).await.ok();
+        }
+
+        Ok(entry)
+    }
+
+    /// 创建目录
+    pub async fn mkdir(&self, session_id: &str, path: &str) -> Result<()> {
+        let sftp = self.get_or_open(session_id).await?;
+
+        sftp.create_dir(path)
+            .await
+            .map_err(|e| anyhow!("Failed to create directory {}: {}", path, e))
+    }
+
+    /// 删除文件或目录
+    /// - recursive 为 true 时删除目录及其全部内容（不跟随符号链接）
+    pub async fn delete(&self, session_id: &str, path: &str, recursive: bool) -> Result<()> {
+        let sftp = self.get_or_open(session_id).await?;
+
+        let metadata = sftp
+            .symlink_metadata(path)
+            .await
+            .map_err(|e| anyhow!("Failed to stat {}: {}", path, e))?;
+
+        if !metadata.file_type().is_dir() {
+            return sftp
+                .remove_file(path)
+                .await
+                .map_err(|e| anyhow!("Failed to delete {}: {}", path, e));
+        }
+
+        if !recursive {
+            return sftp
+                .remove_dir(path)
+                .await
+                .map_err(|e| anyhow!("Failed to delete directory {}: {}", path, e));
+        }
+
+        // 用显式栈做后序遍历，先删除目录内容再删除目录本身
+        let mut stack = vec![(path.to_string(), false)];
+        while let Some((dir, visited)) = stack.pop() {
+            if visited {
+                sftp.remove_dir(dir.as_str())
+                    .await
+                    .map_err(|e| anyhow!("Failed to delete directory {}: {}", dir, e))?;
+                continue;
+            }
+
+            stack.push((dir.clone(), true));
+            let entries = sftp
+                .read_dir(dir.as_str())
+                .await
+                .map_err(|e| anyhow!("Failed to read directory {}: {}", dir, e))?;
+            for entry in entries {
+                let name = entry.file_name();
+                if name == "." || name == ".." {
+                    continue;
+                }
+
+                let child = join_path(&dir, &name);
+                if entry.file_type().is_dir() {
+                    stack.push((child, false));
+                } else {
+                    sftp.remove_file(child.as_str())
+                        .await
+                        .map_err(|e| anyhow!("Failed to delete {}: {}", child, e))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 重命名（移动）文件或目录
+    pub async fn rename(&self, session_id: &str, old_path: &str, new_path: &str) -> Result<()> {
+        let sftp = self.get_or_open(session_id).await?;
+
+        sftp.rename(old_path, new_path)
+            .await
+            .map_err(|e| anyhow!("Failed to rename {} to {}: {}", old_path, new_path, e))
+    }
+
+    /// 修改权限
+    pub async fn chmod(&self, session_id: &str, path: &str, mode: u32) -> Result<()> {
+        let sftp = self.get_or_open(session_id).await?;
+
+        let attributes = FileAttributes {
+            permissions: Some(mode & 0o7777),
+            ..FileAttributes::empty()
+        };
+        sftp.set_metadata(path, attributes)
+            .await
+            .map_err(|e| anyhow!("Failed to chmod {}: {}", path, e))
+    }
+}
+
+/// 在连接上打开 SFTP 子系统
+async fn open_sftp(transport: &RwLock<Handle<SSHClientHandler>>) -> Result<SftpSession> {
+    let channel = transport
+        .read()
+        .await
+        .channel_open_session()
+        .await
+        .map_err(|e| anyhow!("Failed to open SFTP channel: {}", e))?;
+    channel
+        .request_subsystem(true, "sftp")
+        .await
+        .map_err(|e| anyhow!("Failed to request SFTP subsystem: {}", e))?;
+
+    SftpSession::new(channel.into_stream())
+        .await
+        .map_err(|e| anyhow!("Failed to initialize SFTP session: {}", e))
+}
+
+/// 读取远程主机的用户和组（失败时返回空表，只显示 uid/gid）
+async fn load_id_names(transport: &RwLock<Handle<SSHClientHandler>>) -> IdNames {
+    match run_command(
+        transport,
+        ID_NAMES_COMMAND,
+        Some(ID_NAMES_TIMEOUT),
+        None,
+        |_, _| {},
+    )
+    .await
+    {
+        Ok(output) if !output.timed_out => parse_id_names(&String::from_utf8_lossy(&output.stdout)),
+        Ok(_) => {
+            log::warn!("Timed out reading remote users and groups");
+            IdNames::default()
+        }
+        Err(e) => {
+            log::warn!("Failed to read remote users and groups: {}", e);
+            IdNames::default()
+        }
+    }
+}
+
+/// 解析 ID_NAMES_COMMAND 的输出（passwd 和 group 格式均为 name:x:id:...）
+fn parse_id_names(output: &str) -> IdNames {
+    let mut names = IdNames::default();
+    let mut in_groups = false;
+    for line in output.lines() {
+        if line.trim() == "@@group" {
+            in_groups = true;
+            continue;
+        }
+
+        let mut fields = line.split(':');
+        let (Some(name), Some(id)) = (fields.next(), fields.nth(1)) else {
+            continue;
+        };
+        let Ok(id) = id.trim().parse::<u32>() else {
+            continue;
+        };
+        if name.is_empty() || name.starts_with('#') {
+            continue;
+        }
+
+        // 同一 id 有多个名称时取第一个（与 ls 一致）
+        let map = if in_groups {
+            &mut names.groups
+        } else {
+            &mut names.users
+        };
+        map.entry(id).or_insert_with(|| name.to_string());
+    }
+    names
+}
+
+/// 拼接远程路径（远程路径统一使用 /）
+pub fn join_path(dir: &str, name: &str) -> String {
+    if dir.is_empty() || dir == "." {
+        name.to_string()
+    } else if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// 获取远程路径的文件名部分
+fn file_name(path: &str) -> String {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        return "/".to_string();
+    }
+    trimmed.rsplit('/').next().unwrap_or(trimmed).to_string()
+}
+
+/// 生成 ls -l 风格的权限字符串
+pub fn format_permissions(file_type: SftpFileType, mode: u32) -> String {
+    let type_char = match file_type {
+        SftpFileType::Dir => 'd',
+        SftpFileType::Symlink => 'l',
+        SftpFileType::File => '-',
+        SftpFileType::Other => '?',
+    };
+
+    let bit = |mask: u32, c: char| if mode & mask != 0 { c } else { '-' };
+    let exec = |mask: u32, special: u32, set: char, unset: char| match (
+        mode & mask != 0,
+        mode & special != 0,
+    ) {
+        (true, true) => set,
+        (false, true) => unset,
+        (true, false) => 'x',
+        (false, false) => '-',
+    };
+
+    let mut s = String::with_capacity(10);
+    s.push(type_char);
+    s.push(bit(0o400, 'r'));
+    s.push(bit(0o200, 'w'));
+    s.push(exec(0o100, 0o4000, 's', 'S'));
+    s.push(bit(0o040, 'r'));
+    s.push(bit(0o020, 'w'));
+    s.push(exec(0o010, 0o2000, 's', 'S'));
+    s.push(bit(0o004, 'r'));
+    s.push(bit(0o002, 'w'));
+    s.push(exec(0o001, 0o1000, 't', 'T'));
+    s
+}
+
+/// 全局 SFTP 管理器
+static SFTP_MANAGER: Lazy<SftpManager> = Lazy::new(SftpManager::new);
+
+pub fn get_sftp_manager() -> &'static SftpManager {
+    &SFTP_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_path() {
+        assert_eq!(join_path("/", "etc"), "/etc");
+        assert_eq!(join_path("/var/log", "syslog"), "/var/log/syslog");
+        assert_eq!(join_path("/var/log/", "syslog"), "/var/log/syslog");
+        assert_eq!(join_path(".", "notes.txt"), "notes.txt");
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("/var/log/syslog"), "syslog");
+        assert_eq!(file_name("/var/log/"), "log");
+        assert_eq!(file_name("/"), "/");
+        assert_eq!(file_name("notes.txt"), "notes.txt");
+    }
+
+    #[test]
+    fn test_parse_id_names() {
+        let output = "root:x:0:0:root:/root:/bin/bash\n\
+                      # comment\n\
+                      deploy:x:1000:1000::/home/deploy:/bin/sh\n\
+                      toor:x:0:0::/root:/bin/sh\n\
+                      @@group\n\
+                      root:x:0:\n\
+                      www-data:x:33:deploy\n";
+        let names = parse_id_names(output);
+
+        assert_eq!(names.user(0).as_deref(), Some("root"));
+        assert_eq!(names.user(1000).as_deref(), Some("deploy"));
+        assert_eq!(names.user(33), None);
+        assert_eq!(names.group(33).as_deref(), Some("www-data"));
+        assert_eq!(names.group(1000), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_id_names_command_output_parses() {
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(ID_NAMES_COMMAND)
+            .output()
+            .unwrap();
+        let names = parse_id_names(&String::from_utf8_lossy(&output.stdout));
+
+        assert_eq!(names.user(0).as_deref(), Some("root"));
+        assert!(names.group(0).is_some());
+    }
+
+    #[test]
+    fn test_format_permissions() {
+        assert_eq!(format_permissions(SftpFileType::Dir, 0o755), "drwxr-xr-x");
+        assert_eq!(format_permissions(SftpFileType::File, 0o644), "-rw-r--r--");
+        assert_eq!(
+            format_permissions(SftpFileType::Symlink, 0o777),
+            "lrwxrwxrwx"
+        );
+        assert_eq!(format_permissions(SftpFileType::File, 0o4755), "-rwsr-xr-x");
+        assert_eq!(format_permissions(SftpFileType::Dir, 0o1777), "drwxrwxrwt");
+        assert_eq!(format_permissions(SftpFileType::File, 0o2640), "-rw-r-S---");
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Result};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const SOCKS_VERSION: u8 = 0x05;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_NOT_ACCEPTABLE: u8 = 0xFF;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// SOCKS5 应答码（RFC 1928）
+pub const REPLY_SUCCEEDED: u8 = 0x00;
+pub const REPLY_CONNECTION_REFUSED: u8 = 0x05;
+pub const REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+pub const REPLY_ADDRESS_TYPE_NOT_SUPPORTED: u8 = 0x08;
+
+/// SOCKS5 CONNECT 请求的目标地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetAddr {
+    Ip(SocketAddr),
+    Domain(String, u16),
+}
+
+impl TargetAddr {
+    pub fn host(&self) -> String {
+        match self {
+            TargetAddr::Ip(addr) => addr.ip().to_string(),
+            TargetAddr::Domain(domain, _) => domain.clone(),
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        match self {
+            TargetAddr::Ip(addr) => addr.port(),
+            TargetAddr::Domain(_, port) => *port,
+        }
+    }
+}
+
+/// 完成 SOCKS5 握手并读取 CONNECT 请求
+/// - 仅支持无认证方式和 CONNECT 命令，其它情况会先回复错误码再返回错误
+pub async fn handshake<S>(stream: &mut S) -> Result<TargetAddr>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // 协商认证方式
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    if header[0] != SOCKS_VERSION {
+        return Err(anyhow!("Unsupported SOCKS version: {}", header[0]));
+    }
+
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).await?;
+    if !methods.contains(&METHOD_NO_AUTH) {
+        stream
+            .write_all(&[SOCKS_VERSION, METHOD_NOT_ACCEPTABLE])
+            .await?;
+        return Err(anyhow!("Client does not support no-auth method"));
+    }
+    stream.write_all(&[SOCKS_VERSION, METHOD_NO_AUTH]).await?;
+
+    // 读取请求
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    if request[0] != SOCKS_VERSION {
+        return Err(anyhow!("Unsupported SOCKS version: {}", request[0]));
+    }
+    if request[1] != CMD_CONNECT {
+        send_reply(stream, REPLY_COMMAND_NOT_SUPPORTED).await?;
+        return Err(anyhow!("Unsupported SOCKS command: {}", request[1]));
+    }
+
+    let target = match request[3] {
+        ATYP_IPV4 => {
+            let mut octets = [0u8; 4];
+            stream.read_exact(&mut octets).await?;
+            let port = stream.read_u16().await?;
+            TargetAddr::Ip(SocketAddr::new(Ipv4Addr::from(octets).into(), port))
+        }
+        ATYP_IPV6 => {
+            let mut octets = [0u8; 16];
+            stream.read_exact(&mut octets).await?;
+            let port = stream.read_u16().await?;
+            TargetAddr::Ip(SocketAddr::new(Ipv6Addr::from(octets).into(), port))
+        }
+        ATYP_DOMAIN => {
+            let len = stream.read_u8().await?;
+            let mut domain = vec![0u8; len as usize];
+            stream.read_exact(&mut domain).await?;
+            let port = stream.read_u16().await?;
+            let domain = String::from_utf8(domain).map_err(|_| anyhow!("Invalid domain name"))?;
+            TargetAddr::Domain(domain, port)
+        }
+        other => {
+            send_reply(stream, REPLY_ADDRESS_TYPE_NOT_SUPPORTED).await?;
+            return Err(anyhow!("Unsupported address type: {}", other));
+        }
+    };
+
+    Ok(target)
+}
+
+/// 发送 SOCKS5 应答（绑定地址固定为 0.0.0.0:0）
+pub async fn send_reply<S>(stream: &mut S, reply: u8) -> Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    stream
+        .write_all(&[SOCKS_VERSION, reply, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run_handshake(request: &[u8]) -> (Result<TargetAddr>, Vec<u8>) {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        client.write_all(request).await.unwrap();
+
+        let result = handshake(&mut server).await;
+        drop(server);
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        (result, response)
+    }
+
+    #[tokio::test]
+    async fn test_connect_ipv4() {
+        let (result, response) =
+            run_handshake(&[5, 1, 0, 5, 1, 0, 1, 10, 0, 0, 8, 0x1F, 0x90]).await;
+
+        assert_eq!(
+            result.unwrap(),
+            TargetAddr::Ip("10.0.0.8:8080".parse().unwrap())
+        );
+        assert_eq!(response, vec![5, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_connect_domain() {
+        let mut request = vec![5, 2, 2, 0, 5, 1, 0, 3, 11];
+        request.extend_from_slice(b"grafana.lan");
+        request.extend_from_slice(&[0x0B, 0xB8]);
+
+        let (result, _) = run_handshake(&request).await;
+        let target = result.unwrap();
+        assert_eq!(target.host(), "grafana.lan");
+        assert_eq!(target.port(), 3000);
+    }
+
+    #[tokio::test]
+    async fn test_connect_ipv6() {
+        let mut request = vec![5, 1, 0, 5, 1, 0, 4];
+        request.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        request.extend_from_slice(&[0, 80]);
+
+        let (result, _) = run_handshake(&request).await;
+        assert_eq!(result.unwrap(), TargetAddr::Ip("[::1]:80".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_auth_only_clients() {
+        let (result, response) = run_handshake(&[5, 1, 2]).await;
+        assert!(result.is_err());
+        assert_eq!(response, vec![5, 0xFF]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bind_command() {
+        let (result, response) = run_handshake(&[5, 1, 0, 5, 2, 0, 1, 127, 0, 0, 1, 0, 80]).await;
+        assert!(result.is_err());
+        assert_eq!(response[3], REPLY_COMMAND_NOT_SUPPORTED);
+    }
+}
@@ -0,0 +1,542 @@
+use super::client::get_ssh_manager;
+use super::pool::ConnectionLease;
+use super::sftp::get_sftp_manager;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use russh_sftp::protocol::OpenFlags;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// 每次读写的块大小
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// 进度事件的最小发送间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 传输方向
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// 传输状态
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// 传输信息（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferInfo {
+    pub id: String,
+    pub session_id: String,
+    pub direction: TransferDirection,
+    pub local_path: String,
+    pub remote_path: String,
+    pub offset: u64,
+    pub transferred: u64,
+    pub total: u64,
+    pub status: TransferStatus,
+    pub started_at: String,
+}
+
+/// 传输进度
+/// - offset: 续传起点，transferred/total 均包含续传前已有的部分
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    pub transferred: u64,
+    pub total: u64,
+    /// 本次传输的平均速率（字节/秒）
+    pub rate: f64,
+    /// 预计剩余时间（秒），速率未知时为 None
+    pub eta_seconds: Option<u64>,
+}
+
+impl TransferProgress {
+    fn compute(
+        transfer_id: &str,
+        offset: u64,
+        transferred: u64,
+        total: u64,
+        elapsed: Duration,
+    ) -> Self {
+        let secs = elapsed.as_secs_f64();
+        let rate = if secs > 0.0 {
+            transferred.saturating_sub(offset) as f64 / secs
+        } else {
+            0.0
+        };
+        let eta_seconds = if rate > 0.0 {
+            Some((total.saturating_sub(transferred) as f64 / rate).ceil() as u64)
+        } else {
+            None
+        };
+
+        Self {
+            transfer_id: transfer_id.to_string(),
+            transferred,
+            total,
+            rate,
+            eta_seconds,
+        }
+    }
+}
+
+/// 进行中的传输
+struct Transfer {
+    info: TransferInfo,
+    transferred: Arc<AtomicU64>,
+    cancelled: Arc<AtomicBool>,
+    /// 通过连接池打开的 SFTP 所在连接的使用权，传输结束时释放
+    lease: Option<Arc<ConnectionLease>>,
+}
+
+/// SFTP 传输管理器
+pub struct TransferManager {
+    transfers: Mutex<HashMap<String, Transfer>>,
+}
+
+impl TransferManager {
+    pub fn new() -> Self {
+        Self {
+            transfers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 上传本地文件，返回传输 ID
+    /// - resume 为 true 时从远程文件已有的长度处续传
+    pub async fn upload(
+        &self,
+        session_id: &str,
+        local_path: &str,
+        remote_path: &str,
+        resume: bool,
+    ) -> Result<String> {
+        let app_handle = get_ssh_manager().app_handle().await?;
+        let sftp = get_sftp_manager().get_or_open(session_id).await?;
+        let lease = get_sftp_manager().lease(session_id).await;
+
+        let mut local = tokio::fs::File::open(local_path)
+            .await
+            .map_err(|e| anyhow!("Failed to open {}: {}", local_path, e))?;
+        let total = local
+            .metadata()
+            .await
+            .map_err(|e| anyhow!("Failed to stat {}: {}", local_path, e))?
+            .len();
+
+        let offset = if resume {
+            match sftp.metadata(remote_path).await {
+                Ok(metadata) => metadata.size.unwrap_or(0).min(total),
+                Err(_) => 0,
+            }
+        } else {
+            0
+        };
+
+        let flags = if offset > 0 {
+            OpenFlags::WRITE | OpenFlags::CREATE
+        } else {
+            OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE
+        };
+        let mut remote = sftp
+            .open_with_flags(remote_path, flags)
+            .await
+            .map_err(|e| anyhow!("Failed to open remote file {}: {}", remote_path, e))?;
+
+        if offset > 0 {
+            local.seek(SeekFrom::Start(offset)).await?;
+            remote.seek(SeekFrom::Start(offset)).await?;
+        }
+
+        let (transfer_id, transferred, cancelled) = self
+            .register(
+                session_id,
+                lease,
+                TransferDirection::Upload,
+                local_path,
+                remote_path,
+                offset,
+                total,
+            )
+            .await;
+
+        tokio::spawn(run_transfer(
+            app_handle,
+            transfer_id.clone(),
+            local,
+            remote,
+            offset,
+            total,
+            transferred,
+            cancelled,
+        ));
+
+        Ok(transfer_id)
+    }
+
+    /// 下载远程文件，返回传输 ID
+    /// - resume 为 true 时从本地文件已有的长度处续传
+    pub async fn download(
+        &self,
+        session_id: &str,
+        remote_path: &str,
+        local_path: &str,
+        resume: bool,
+    ) -> Result<String> {
+        let app_handle = get_ssh_manager().app_handle().await?;
+        let sftp = get_sftp_manager().get_or_open(session_id).await?;
+        let lease = get_sftp_manager().lease(session_id).await;
+
+        let total = sftp
+            .metadata(remote_path)
+            .await
+            .map_err(|e| anyhow!("Failed to stat {}: {}", remote_path, e))?
+            .size
+            .unwrap_or(0);
+
+        let offset = if resume {
+            match tokio::fs::metadata(local_path).await {
+                Ok(metadata) => metadata.len().min(total),
+                Err(_) => 0,
+            }
+        } else {
+            0
+        };
+
+        let mut remote = sftp
+            .open_with_flags(remote_path, OpenFlags::READ)
+            .await
+            .map_err(|e| anyhow!("Failed to open remote file {}: {}", remote_path, e))?;
+
+        let mut local = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(offset == 0)
+            .open(local_path)
+            .await
+            .map_err(|e| anyhow!("Failed to open {}: {}", local_path, e))?;
+
+        if offset > 0 {
+            remote.seek(SeekFrom::Start(offset)).await?;
+            local.seek(SeekFrom::Start(offset)).await?;
+        }
+
+        let (transfer_id, transferred, cancelled) = self
+            .register(
+                session_id,
+                lease,
+                TransferDirection::Download,
+                local_path,
+                remote_path,
+                offset,
+                total,
+            )
+            .await;
+
+        tokio::spawn(run_transfer(
+            app_handle,
+            transfer_id.clone(),
+            remote,
+            local,
+            offset,
+            total,
+            transferred,
+            cancelled,
+        ));
+
+        Ok(transfer_id)
+    }
+
+    /// 取消传输（已写入的部分会保留，可用于续传）
+    pub async fn cancel(&self, transfer_id: &str) -> Result<()> {
+        let transfers = self.transfers.lock().await;
+        let transfer = transfers
+            .get(transfer_id)
+            .ok_or_else(|| anyhow!("Transfer not found: {}", transfer_id))?;
+
+        transfer.cancelled.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 列出进行中的传输（可按会话过滤）
+    pub async fn list(&self, session_id: Option<&str>) -> Vec<TransferInfo> {
+        self.transfers
+            .lock()
+            .await
+            .values()
+            .filter(|transfer| session_id.map_or(true, |id| transfer.info.session_id == id))
+            .map(|transfer| {
+                let mut info = transfer.info.clone();
+                info.transferred = transfer.transferred.load(Ordering::Relaxed);
+                info
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn register(
+        &self,
+        session_id: &str,
+        lease: Option<Arc<ConnectionLease>>,
+        direction: TransferDirection,
+        local_path: &str,
+        remote_path: &str,
+        offset: u64,
+        total: u64,
+    ) -> (String, Arc<AtomicU64>, Arc<AtomicBool>) {
+        let transfer_id = Uuid::new_v4().to_string();
+        let transferred = Arc::new(AtomicU64::new(offset));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let transfer = Transfer {
+            info: TransferInfo {
+                id: transfer_id.clone(),
+                session_id: session_id.to_string(),
+                direction,
+                local_path: local_path.to_string(),
+                remote_path: remote_path.to_string(),
+                offset,
+                transferred: offset,
+                total,
+                status: TransferStatus::Running,
+                started_at: Utc::now().to_rfc3339(),
+            },
+            transferred: transferred.clone(),
+            cancelled: cancelled.clone(),
+            lease,
+        };
+
+        self.transfers
+            .lock()
+            .await
+            .insert(transfer_id.clone(), transfer);
+
+        (transfer_id, transferred, cancelled)
+    }
+
+    /// 传输结束后移除记录（同时释放连接使用权），结果已通过完成事件通知前端
+    async fn finish(&self, transfer_id: &str) {
+        self.transfers.lock().await.remove(transfer_id);
+    }
+}
+
+/// 按块复制数据，定期发送进度事件，结束后发送完成事件
+#[allow(clippy::too_many_arguments)]
+async fn run_transfer<R, W>(
+    app_handle: tauri::AppHandle,
+    transfer_id: String,
+    reader: R,
+    writer: W,
+    offset: u64,
+    total: u64,
+    transferred: Arc<AtomicU64>,
+    cancelled: Arc<AtomicBool>,
+) where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let progress_event = format!("sftp-progress-{}", transfer_id);
+    let started = Instant::now();
+
+    let result = copy_chunks(
+        reader,
+        writer,
+        &cancelled,
+        |done| {
+            transferred.store(offset + done, Ordering::Relaxed);
+        },
+        |done| {
+            let progress = TransferProgress::compute(
+                &transfer_id,
+                offset,
+                offset + done,
+                total,
+                started.elapsed(),
+            );
+            let _ = app_handle.emit_all(&progress_event, progress);
+        },
+    )
+    .await;
+
+    let (status, error) = match result {
+        Ok(true) => (TransferStatus::Completed, None),
+        Ok(false) => (TransferStatus::Cancelled, None),
+        Err(e) => (TransferStatus::Failed, Some(e.to_string())),
+    };
+
+    let _ = app_handle.emit_all(
+        &format!("sftp-complete-{}", transfer_id),
+        json!({
+            "transfer_id": transfer_id,
+            "status": status,
+            "transferred": transferred.load(Ordering::Relaxed),
+            "total": total,
+            "error": error,
+        }),
+    );
+
+    get_transfer_manager().finish(&transfer_id).await;
+}
+
+/// 按块复制，返回 Ok(false) 表示被取消
+/// - on_chunk 每块调用一次，on_progress 按 PROGRESS_INTERVAL 节流调用（结束时必定调用一次）
+async fn copy_chunks<R, W>(
+    mut reader: R,
+    mut writer: W,
+    cancelled: &AtomicBool,
+    mut on_chunk: impl FnMut(u64),
+    mut on_progress: impl FnMut(u64),
+) -> Result<bool>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut done: u64 = 0;
+    let mut last_progress = Instant::now();
+
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            writer.shutdown().await?;
+            on_progress(done);
+            return Ok(false);
+        }
+
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n]).await?;
+        done += n as u64;
+        on_chunk(done);
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(done);
+            last_progress = Instant::now();
+        }
+    }
+
+    writer.flush().await?;
+    writer.shutdown().await?;
+    on_progress(done);
+    Ok(true)
+}
+
+/// 全局传输管理器
+static TRANSFER_MANAGER: Lazy<TransferManager> = Lazy::new(TransferManager::new);
+
+pub fn get_transfer_manager() -> &'static TransferManager {
+    &TRANSFER_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_rate_and_eta() {
+        let progress =
+            TransferProgress::compute("t1", 0, 50 * 1024, 100 * 1024, Duration::from_secs(5));
+        assert_eq!(progress.rate, 10.0 * 1024.0);
+        assert_eq!(progress.eta_seconds, Some(5));
+    }
+
+    #[test]
+    fn test_progress_excludes_resumed_offset_from_rate() {
+        let progress = TransferProgress::compute("t1", 900, 1000, 2000, Duration::from_secs(1));
+        assert_eq!(progress.rate, 100.0);
+        assert_eq!(progress.eta_seconds, Some(10));
+    }
+
+    #[test]
+    fn test_progress_without_elapsed_time() {
+        let progress = TransferProgress::compute("t1", 0, 0, 100, Duration::ZERO);
+        assert_eq!(progress.rate, 0.0);
+        assert_eq!(progress.eta_seconds, None);
+    }
+
+    #[tokio::test]
+    async fn test_copy_chunks_stops_when_cancelled() {
+        let data = vec![7u8; CHUNK_SIZE * 3];
+        let cancelled = AtomicBool::new(false);
+        let mut output = Vec::new();
+
+        let finished = copy_chunks(
+            &data[..],
+            &mut output,
+            &cancelled,
+            |done| {
+                if done >= CHUNK_SIZE as u64 {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+            },
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert!(!finished);
+        assert_eq!(output.len(), CHUNK_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_copy_chunks_copies_everything() {
+        let data: Vec<u8> = (0..(CHUNK_SIZE * 2 + 17)).map(|i| i as u8).collect();
+        let cancelled = AtomicBool::new(false);
+        let mut output = Vec::new();
+        let mut last = 0;
+
+        let finished = copy_chunks(
+            &data[..],
+            &mut output,
+            &cancelled,
+            |_| {},
+            |done| last = done,
+        )
+        .await
+        .unwrap();
+
+        assert!(finished);
+        assert_eq!(output, data);
+        assert_eq!(last, data.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_finished_transfer_removed_from_list() {
+        let manager = TransferManager::new();
+        let (transfer_id, _, _) = manager
+            .register(
+                "s1",
+                None,
+                TransferDirection::Upload,
+                "/tmp/a",
+                "/remote/a",
+                0,
+                10,
+            )
+            .await;
+        assert_eq!(manager.list(Some("s1")).await.len(), 1);
+
+        manager.finish(&transfer_id).await;
+        assert!(manager.list(None).await.is_empty());
+        assert!(manager.cancel(&transfer_id).await.is_err());
+    }
+}
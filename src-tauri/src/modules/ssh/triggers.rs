@@ -0,0 +1,552 @@
+use super::client::get_ssh_manager;
+use super::recorder::get_recording_manager;
+use crate::modules::database::get_db;
+use crate::utils::utf8::Utf8Decoder;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use uuid::Uuid;
+
+/// 当前行最多保留的字符数（超出时丢弃行首）
+const MAX_LINE_CHARS: usize = 4096;
+
+/// 同一规则在同一会话中两次触发的最小间隔（避免刷屏和自动发送循环）
+const MIN_FIRE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 触发后执行的动作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TriggerAction {
+    /// 在终端中高亮匹配内容（由前端根据 ssh-trigger-{id} 事件渲染）
+    Highlight {
+        #[serde(default)]
+        color: Option<String>,
+    },
+    /// 发送 ssh-notification 事件，由前端弹出桌面通知
+    Notify {
+        #[serde(default)]
+        title: Option<String>,
+    },
+    /// 自动向会话发送文本（需要回车时在末尾加 \r）
+    Send { text: String },
+    /// 停止会话录制
+    StopRecording,
+}
+
+/// 输出触发规则（对应 devhub.db 中的 triggers 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trigger {
+    pub id: String,
+    pub name: String,
+    /// 仅对该已保存连接的会话生效，None 表示对所有会话生效
+    pub connection_id: Option<String>,
+    /// 正则表达式，按行匹配去掉控制序列后的输出（忽略大小写可使用 (?i)）
+    pub pattern: String,
+    pub actions: Vec<TriggerAction>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 编译后的规则
+struct CompiledTrigger {
+    trigger: Trigger,
+    regex: Regex,
+}
+
+/// 一次触发
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerHit {
+    pub trigger_id: String,
+    pub name: String,
+    /// 匹配到的文本
+    pub matched: String,
+    /// 匹配所在的行
+    pub line: String,
+    pub actions: Vec<TriggerAction>,
+}
+
+/// 触发规则管理器
+/// - 启用的规则编译后缓存在内存中，修改规则后递增版本号，各会话的匹配器按版本号重新加载
+pub struct TriggerManager {
+    rules: RwLock<Arc<Vec<Arc<CompiledTrigger>>>>,
+    version: AtomicU64,
+    loaded: tokio::sync::OnceCell<()>,
+}
+
+impl TriggerManager {
+    pub fn new() -> Self {
+        Self {
+            rules: RwLock::new(Arc::new(Vec::new())),
+            version: AtomicU64::new(0),
+            loaded: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// 首次使用时从数据库加载规则
+    pub async fn ensure_loaded(&self) -> Result<()> {
+        self.loaded
+            .get_or_try_init(|| async { self.reload().await })
+            .await?;
+        Ok(())
+    }
+
+    /// 从数据库重新加载启用的规则（规则或其所属连接被修改、删除后调用）
+    pub async fn reload(&self) -> Result<()> {
+        let mut compiled = Vec::new();
+        for trigger in list(None).await? {
+            if !trigger.enabled {
+                continue;
+            }
+            match Regex::new(&trigger.pattern) {
+                Ok(regex) => compiled.push(Arc::new(CompiledTrigger { trigger, regex })),
+                Err(e) => log::warn!(
+                    "Skipping trigger {} with invalid pattern: {}",
+                    trigger.id,
+                    e
+                ),
+            }
+        }
+
+        *self.rules.write().unwrap() = Arc::new(compiled);
+        self.version.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    fn rules(&self) -> Arc<Vec<Arc<CompiledTrigger>>> {
+        self.rules.read().unwrap().clone()
+    }
+}
+
+/// 单个会话的输出匹配器
+/// - 按行匹配：换行或回车结束一行；尚未结束的行（如 sudo 的密码提示）同样参与匹配
+/// - 同一行上每条规则只触发一次
+pub struct TriggerMatcher {
+    connection_id: Option<String>,
+    version: u64,
+    rules: Vec<Arc<CompiledTrigger>>,
+    decoder: Utf8Decoder,
+    escape: EscapeState,
+    line: String,
+    /// 当前行上已触发的规则
+    fired: HashSet<String>,
+    last_fired: Vec<(String, Instant)>,
+}
+
+impl TriggerMatcher {
+    pub fn new(connection_id: Option<String>) -> Self {
+        Self {
+            connection_id,
+            version: 0,
+            rules: Vec::new(),
+            decoder: Utf8Decoder::new(),
+            escape: EscapeState::Text,
+            line: String::new(),
+            fired: HashSet::new(),
+            last_fired: Vec::new(),
+        }
+    }
+
+    /// 处理一块输出，返回触发的规则
+    pub fn feed(&mut self, data: &[u8]) -> Vec<TriggerHit> {
+        let manager = get_trigger_manager();
+        if self.version != manager.version() {
+            self.version = manager.version();
+            self.set_rules(&manager.rules());
+        }
+        self.feed_at(data, Instant::now())
+    }
+
+    fn set_rules(&mut self, rules: &[Arc<CompiledTrigger>]) {
+        self.rules = rules
+            .iter()
+            .filter(|rule| {
+                rule.trigger.connection_id.is_none()
+                    || rule.trigger.connection_id == self.connection_id
+            })
+            .cloned()
+            .collect();
+    }
+
+    fn feed_at(&mut self, data: &[u8], now: Instant) -> Vec<TriggerHit> {
+        let mut hits = Vec::new();
+        if self.rules.is_empty() {
+            return hits;
+        }
+
+        let text = self.decoder.decode(data);
+        for c in text.chars() {
+            let Some(c) = self.escape.next(c) else {
+                continue;
+            };
+            match c {
+                '\n' | '\r' => {
+                    self.match_line(now, &mut hits);
+                    self.line.clear();
+                    self.fired.clear();
+                }
+                c if c.is_control() && c != '\t' => {}
+                c => self.line.push(c),
+            }
+        }
+
+        if self.line.chars().count() > MAX_LINE_CHARS {
+            let skip = self.line.chars().count() - MAX_LINE_CHARS;
+            self.line = self.line.chars().skip(skip).collect();
+        }
+
+        // 尚未结束的行
+        self.match_line(now, &mut hits);
+        hits
+    }
+
+    fn match_line(&mut self, now: Instant, hits: &mut Vec<TriggerHit>) {
+        if self.line.is_empty() {
+            return;
+        }
+
+        for rule in &self.rules {
+            let id = &rule.trigger.id;
+            if self.fired.contains(id) {
+                continue;
+            }
+            let Some(found) = rule.regex.find(&self.line) else {
+                continue;
+            };
+
+            self.fired.insert(id.clone());
+            match self
+                .last_fired
+                .iter_mut()
+                .find(|(rule_id, _)| rule_id == id)
+            {
+                Some((_, last)) if now.duration_since(*last) < MIN_FIRE_INTERVAL => continue,
+                Some((_, last)) => *last = now,
+                None => self.last_fired.push((id.clone(), now)),
+            }
+
+            hits.push(TriggerHit {
+                trigger_id: id.clone(),
+                name: rule.trigger.name.clone(),
+                matched: found.as_str().to_string(),
+                line: self.line.clone(),
+                actions: rule.trigger.actions.clone(),
+            });
+        }
+    }
+}
+
+/// 终端控制序列过滤状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+    Text,
+    /// 收到 ESC
+    Escape,
+    /// CSI 序列（ESC [ ... 终止字节）
+    Csi,
+    /// 字符集选择等带一个参数字符的序列
+    Param,
+    /// OSC 等字符串序列（以 BEL 或 ESC \ 结束）
+    Str,
+    /// 字符串序列中收到 ESC
+    StrEscape,
+}
+
+impl EscapeState {
+    /// 过滤一个字符，返回可见文本中的字符
+    fn next(&mut self, c: char) -> Option<char> {
+        match (*self, c) {
+            (EscapeState::Text, '\x1b') => *self = EscapeState::Escape,
+            (EscapeState::Text, c) => return Some(c),
+            (EscapeState::Escape, '[') => *self = EscapeState::Csi,
+            (EscapeState::Escape, ']' | 'P' | '_' | '^' | 'X') => *self = EscapeState::Str,
+            (EscapeState::Escape, '(' | ')' | '*' | '+' | '#' | '%') => *self = EscapeState::Param,
+            (EscapeState::Escape, _) => *self = EscapeState::Text,
+            (EscapeState::Csi, '\x40'..='\x7e') => *self = EscapeState::Text,
+            (EscapeState::Csi, _) => {}
+            (EscapeState::Param, _) => *self = EscapeState::Text,
+            (EscapeState::Str, '\x07') => *self = EscapeState::Text,
+            (EscapeState::Str, '\x1b') => *self = EscapeState::StrEscape,
+            (EscapeState::Str, _) => {}
+            (EscapeState::StrEscape, '\\') => *self = EscapeState::Text,
+            (EscapeState::StrEscape, _) => *self = EscapeState::Str,
+        }
+        None
+    }
+}
+
+/// 执行触发的动作
+/// - 每次触发发送 ssh-trigger-{id} 事件（包含匹配内容与动作，前端据此高亮）
+/// - notify 动作额外发送全局 ssh-notification 事件
+pub async fn fire(session_id: &str, hits: Vec<TriggerHit>, app_handle: &tauri::AppHandle) {
+    for hit in hits {
+        log::info!("Trigger {} fired on session {}", hit.trigger_id, session_id);
+        let _ = app_handle.emit_all(&format!("ssh-trigger-{}", session_id), &hit);
+
+        for action in &hit.actions {
+            match action {
+                TriggerAction::Highlight { .. } => {}
+                TriggerAction::Notify { title } => {
+                    let _ = app_handle.emit_all(
+                        "ssh-notification",
+                        json!({
+                            "session_id": session_id,
+                            "trigger_id": hit.trigger_id,
+                            "title": title.as_deref().unwrap_or(&hit.name),
+                            "body": hit.line,
+                        }),
+                    );
+                }
+                TriggerAction::Send { text } => {
+                    let results = get_ssh_manager()
+                        .write_to_sessions(&[session_id.to_string()], text.as_bytes())
+                        .await;
+                    if let Some(error) = results.into_iter().find_map(|r| r.error) {
+                        log::warn!("Trigger {} failed to send: {}", hit.trigger_id, error);
+                    }
+                }
+                TriggerAction::StopRecording => {
+                    if get_recording_manager().is_recording(session_id) {
+                        if let Err(e) = get_recording_manager().stop(session_id).await {
+                            log::warn!(
+                                "Trigger {} failed to stop recording: {}",
+                                hit.trigger_id,
+                                e
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 列出规则（指定 connection_id 时只返回对该连接生效的规则，包括全局规则）
+pub async fn list(connection_id: Option<&str>) -> Result<Vec<Trigger>> {
+    let db = get_db();
+
+    let rows = if let Some(connection_id) = connection_id {
+        sqlx::query_as::<_, TriggerRow>(
+            "SELECT id, name, connection_id, pattern, actions, enabled, created_at, updated_at FROM triggers WHERE connection_id IS NULL OR connection_id = ? ORDER BY created_at",
+        )
+        .bind(connection_id)
+        .fetch_all(db.pool())
+        .await
+    } else {
+        sqlx::query_as::<_, TriggerRow>(
+            "SELECT id, name, connection_id, pattern, actions, enabled, created_at, updated_at FROM triggers ORDER BY created_at",
+        )
+        .fetch_all(db.pool())
+        .await
+    }
+    .map_err(|e| anyhow!("Failed to list triggers: {}", e))?;
+
+    rows.into_iter().map(into_trigger).collect()
+}
+
+/// 创建或更新规则（id 为 None 时创建）
+pub async fn save(
+    id: Option<&str>,
+    name: &str,
+    connection_id: Option<&str>,
+    pattern: &str,
+    actions: &[TriggerAction],
+    enabled: bool,
+) -> Result<Trigger> {
+    Regex::new(pattern).map_err(|e| anyhow!("Invalid pattern: {}", e))?;
+    if actions.is_empty() {
+        return Err(anyhow!("Trigger must have at least one action"));
+    }
+
+    let db = get_db();
+    let id = id
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let actions_json = serde_json::to_string(actions)
+        .map_err(|e| anyhow!("Failed to serialize actions: {}", e))?;
+    let now = Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO triggers (id, name, connection_id, pattern, actions, enabled, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name,
+            connection_id = excluded.connection_id,
+            pattern = excluded.pattern,
+            actions = excluded.actions,
+            enabled = excluded.enabled,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&id)
+    .bind(name)
+    .bind(connection_id)
+    .bind(pattern)
+    .bind(&actions_json)
+    .bind(enabled)
+    .bind(&now)
+    .bind(&now)
+    .execute(db.pool())
+    .await
+    .map_err(|e| anyhow!("Failed to save trigger: {}", e))?;
+
+    get_trigger_manager().reload().await?;
+
+    let row = sqlx::query_as::<_, TriggerRow>(
+        "SELECT id, name, connection_id, pattern, actions, enabled, created_at, updated_at FROM triggers WHERE id = ?",
+    )
+    .bind(&id)
+    .fetch_one(db.pool())
+    .await
+    .map_err(|e| anyhow!("Failed to load trigger: {}", e))?;
+
+    into_trigger(row)
+}
+
+/// 删除规则
+pub async fn delete(id: &str) -> Result<bool> {
+    let db = get_db();
+
+    let result = sqlx::query("DELETE FROM triggers WHERE id = ?")
+        .bind(id)
+        .execute(db.pool())
+        .await
+        .map_err(|e| anyhow!("Failed to delete trigger: {}", e))?;
+
+    get_trigger_manager().reload().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+type TriggerRow = (
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    bool,
+    String,
+    String,
+);
+
+fn into_trigger(row: TriggerRow) -> Result<Trigger> {
+    let (id, name, connection_id, pattern, actions, enabled, created_at, updated_at) = row;
+    let actions = serde_json::from_str(&actions)
+        .map_err(|e| anyhow!("Invalid actions for trigger {}: {}", id, e))?;
+
+    Ok(Trigger {
+        id,
+        name,
+        connection_id,
+        pattern,
+        actions,
+        enabled,
+        created_at,
+        updated_at,
+    })
+}
+
+/// 全局触发规则管理器
+static TRIGGER_MANAGER: Lazy<TriggerManager> = Lazy::new(TriggerManager::new);
+
+pub fn get_trigger_manager() -> &'static TriggerManager {
+    &TRIGGER_MANAGER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(rules: &[(&str, &str)]) -> TriggerMatcher {
+        let rules: Vec<Arc<CompiledTrigger>> = rules
+            .iter()
+            .map(|(id, pattern)| {
+                Arc::new(CompiledTrigger {
+                    trigger: Trigger {
+                        id: id.to_string(),
+                        name: id.to_string(),
+                        connection_id: None,
+                        pattern: pattern.to_string(),
+                        actions: vec![TriggerAction::Highlight { color: None }],
+                        enabled: true,
+                        created_at: String::new(),
+                        updated_at: String::new(),
+                    },
+                    regex: Regex::new(pattern).unwrap(),
+                })
+            })
+            .collect();
+
+        let mut matcher = TriggerMatcher::new(None);
+        matcher.set_rules(&rules);
+        matcher
+    }
+
+    fn ids(hits: &[TriggerHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.trigger_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_matches_across_chunks_and_escapes() {
+        let mut matcher = matcher(&[("error", "ERROR: .*")]);
+        let now = Instant::now();
+
+        assert!(matcher.feed_at(b"deploy \x1b[31mER", now).is_empty());
+        let hits = matcher.feed_at(b"ROR\x1b[0m: disk full\r\n", now);
+        assert_eq!(ids(&hits), ["error"]);
+        assert_eq!(hits[0].matched, "ERROR: disk full");
+        assert_eq!(hits[0].line, "deploy ERROR: disk full");
+    }
+
+    #[test]
+    fn test_partial_line_fires_once() {
+        let mut matcher = matcher(&[("sudo", r"\[sudo\] password for \w+:")]);
+        let now = Instant::now();
+
+        let hits = matcher.feed_at(b"[sudo] password for alice: ", now);
+        assert_eq!(ids(&hits), ["sudo"]);
+        // 同一行继续输出不会重复触发
+        assert!(matcher.feed_at(b"\x1b]0;title\x07", now).is_empty());
+    }
+
+    #[test]
+    fn test_min_fire_interval() {
+        let mut matcher = matcher(&[("error", "ERROR")]);
+        let now = Instant::now();
+
+        assert_eq!(matcher.feed_at(b"ERROR 1\n", now).len(), 1);
+        assert!(matcher.feed_at(b"ERROR 2\n", now).is_empty());
+        let later = now + MIN_FIRE_INTERVAL;
+        assert_eq!(matcher.feed_at(b"ERROR 3\n", later).len(), 1);
+    }
+
+    #[test]
+    fn test_action_serialization() {
+        let actions = vec![
+            TriggerAction::Notify { title: None },
+            TriggerAction::Send {
+                text: "yes\r".to_string(),
+            },
+            TriggerAction::StopRecording,
+        ];
+        let json = serde_json::to_string(&actions).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"type":"notify","title":null},{"type":"send","text":"yes\r"},{"type":"stop_recording"}]"#
+        );
+        let parsed: Vec<TriggerAction> = serde_json::from_str(r#"[{"type":"highlight"}]"#).unwrap();
+        assert_eq!(parsed, [TriggerAction::Highlight { color: None }]);
+    }
+}
@@ -0,0 +1,86 @@
+/// 流式 UTF-8 解码器
+/// - 终端输出按块到达，一个多字节字符可能被拆分到两块中
+/// - 末尾不完整的字节会保留到下一次解码，其它非法字节按 U+FFFD 替换
+#[derive(Debug, Default)]
+pub struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解码一块数据，返回可以安全输出的文本
+    pub fn decode(&mut self, data: &[u8]) -> String {
+        let mut buf = std::mem::take(&mut self.pending);
+        buf.extend_from_slice(data);
+
+        let tail = incomplete_tail_len(&buf);
+        self.pending = buf.split_off(buf.len() - tail);
+
+        String::from_utf8_lossy(&buf).into_owned()
+    }
+}
+
+/// 计算末尾不完整的 UTF-8 序列长度（0 表示末尾完整）
+fn incomplete_tail_len(buf: &[u8]) -> usize {
+    for i in 1..=buf.len().min(3) {
+        let byte = buf[buf.len() - i];
+        if byte & 0b1100_0000 == 0b1000_0000 {
+            // 后续字节，继续向前找起始字节
+            continue;
+        }
+
+        let expected = if byte & 0b1110_0000 == 0b1100_0000 {
+            2
+        } else if byte & 0b1111_0000 == 0b1110_0000 {
+            3
+        } else if byte & 0b1111_1000 == 0b1111_0000 {
+            4
+        } else {
+            1
+        };
+
+        return if expected > i { i } else { 0 };
+    }
+
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ascii() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"ls -la\r\n"), "ls -la\r\n");
+    }
+
+    #[test]
+    fn test_decode_split_multibyte_char() {
+        let bytes = "部署完成".as_bytes();
+        let mut decoder = Utf8Decoder::new();
+
+        let first = decoder.decode(&bytes[..4]);
+        let second = decoder.decode(&bytes[4..]);
+
+        assert_eq!(first, "部");
+        assert_eq!(second, "署完成");
+    }
+
+    #[test]
+    fn test_decode_invalid_bytes_are_replaced() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"a\xFFb"), "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn test_decode_keeps_only_incomplete_tail() {
+        let mut decoder = Utf8Decoder::new();
+        // 😀 = F0 9F 98 80
+        assert_eq!(decoder.decode(b"ok\xF0\x9F"), "ok");
+        assert_eq!(decoder.decode(b"\x98\x80!"), "😀!");
+    }
+}
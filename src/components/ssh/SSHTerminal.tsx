@@ -156,15 +156,18 @@ export function SSHTerminal({ connectionId, onDisconnect, onFitAddonReady }: SSH
         term.writeln('\x1b[1;32m✓ Connected\x1b[0m\r\n')
 
         // 3. 监听 SSH 数据事件
-        unlistenFn = await listen<string>(`ssh-data-${id}`, event => {
+        unlistenFn = await listen<{ offset: number; data: string }>(`ssh-data-${id}`, event => {
           try {
-            const binaryString = atob(event.payload)
+            const binaryString = atob(event.payload.data)
             const bytes = new Uint8Array(binaryString.length)
             for (let i = 0; i < binaryString.length; i++) {
               bytes[i] = binaryString.charCodeAt(i)
             }
             const decoded = new TextDecoder('utf-8').decode(bytes)
-            term.write(decoded)
+            // xterm 处理完成后确认字节数，后端据此流控
+            term.write(decoded, () => {
+              invoke('ssh_ack_output', { sessionId: id, bytes: bytes.length }).catch(() => {})
+            })
           } catch (err) {
             console.error('Failed to decode SSH data:', err)
           }